@@ -1,142 +1,96 @@
-use clap::Parser;
+use advent_of_code::{replay, server, viz, Day, DAYS};
+use clap::{Parser, Subcommand};
 use itertools::Itertools;
-use std::collections::HashMap;
+use std::{path::PathBuf, time::Duration};
 
 #[derive(Parser)]
-#[command(version, about)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Year to run
     year: Option<u32>,
     /// Days to run
     days: Vec<usize>,
+    /// Write frames of simulations as PNG images into this directory
+    #[arg(long, value_name = "DIR")]
+    viz: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve the solvers over HTTP/JSON on localhost
+    Serve {
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Seconds a solver may run before its request fails
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+    },
+    /// Solve the input on stdin, printing the answers as JSON, for `serve`
+    #[command(hide = true)]
+    Solve {
+        /// Year of the puzzle
+        year: u32,
+        /// Day of the puzzle
+        day: u32,
+    },
+    /// Watch a simulation step by step in the terminal
+    Replay {
+        /// Year of the puzzle
+        year: u32,
+        /// Day of the puzzle
+        day: u32,
+    },
 }
 
 fn main() {
     let args = Args::parse();
 
-    let solvers: HashMap<u32, Vec<fn()>> = HashMap::from([
-        (
-            2015,
-            vec![
-                advent_of_code::y2015::day_01::solve,
-                advent_of_code::y2015::day_02::solve,
-                advent_of_code::y2015::day_03::solve,
-                advent_of_code::y2015::day_04::solve,
-                advent_of_code::y2015::day_05::solve,
-                advent_of_code::y2015::day_06::solve,
-                advent_of_code::y2015::day_07::solve,
-                advent_of_code::y2015::day_08::solve,
-                advent_of_code::y2015::day_09::solve,
-                advent_of_code::y2015::day_10::solve,
-                advent_of_code::y2015::day_11::solve,
-                advent_of_code::y2015::day_12::solve,
-                advent_of_code::y2015::day_13::solve,
-                advent_of_code::y2015::day_14::solve,
-                advent_of_code::y2015::day_15::solve,
-                advent_of_code::y2015::day_16::solve,
-                advent_of_code::y2015::day_17::solve,
-                advent_of_code::y2015::day_18::solve,
-            ],
-        ),
-        (
-            2022,
-            vec![
-                advent_of_code::y2022::day01::solve,
-                advent_of_code::y2022::day02::solve,
-                advent_of_code::y2022::day03::solve,
-                advent_of_code::y2022::day04::solve,
-                advent_of_code::y2022::day05::solve,
-                advent_of_code::y2022::day06::solve,
-                advent_of_code::y2022::day07::solve,
-                advent_of_code::y2022::day08::solve,
-                advent_of_code::y2022::day09::solve,
-                advent_of_code::y2022::day10::solve,
-                advent_of_code::y2022::day11::solve,
-                advent_of_code::y2022::day12::solve,
-                advent_of_code::y2022::day13::solve,
-                advent_of_code::y2022::day14::solve,
-                advent_of_code::y2022::day15::solve,
-                advent_of_code::y2022::day16::solve,
-                advent_of_code::y2022::day17::solve,
-                advent_of_code::y2022::day18::solve,
-                advent_of_code::y2022::day19::solve,
-                advent_of_code::y2022::day20::solve,
-                advent_of_code::y2022::day21::solve,
-                advent_of_code::y2022::day22::solve,
-                advent_of_code::y2022::day23::solve,
-                advent_of_code::y2022::day24::solve,
-                advent_of_code::y2022::day25::solve,
-            ],
-        ),
-        (
-            2023,
-            vec![
-                advent_of_code::y2023::day_01::solve,
-                advent_of_code::y2023::day_02::solve,
-                advent_of_code::y2023::day_03::solve,
-                advent_of_code::y2023::day_04::solve,
-                advent_of_code::y2023::day_05::solve,
-                advent_of_code::y2023::day_06::solve,
-                advent_of_code::y2023::day_07::solve,
-                advent_of_code::y2023::day_08::solve,
-                advent_of_code::y2023::day_09::solve,
-                advent_of_code::y2023::day_10::solve,
-            ],
-        ),
-        (
-            2024,
-            vec![
-                advent_of_code::y2024::day01::solve,
-                advent_of_code::y2024::day02::solve,
-                advent_of_code::y2024::day03::solve,
-                advent_of_code::y2024::day04::solve,
-                advent_of_code::y2024::day05::solve,
-                advent_of_code::y2024::day06::solve,
-                advent_of_code::y2024::day07::solve,
-                advent_of_code::y2024::day08::solve,
-                advent_of_code::y2024::day09::solve,
-                advent_of_code::y2024::day10::solve,
-                advent_of_code::y2024::day11::solve,
-                advent_of_code::y2024::day12::solve,
-                advent_of_code::y2024::day13::solve,
-                advent_of_code::y2024::day14::solve,
-                advent_of_code::y2024::day15::solve,
-                advent_of_code::y2024::day16::solve,
-                advent_of_code::y2024::day17::solve,
-                advent_of_code::y2024::day18::solve,
-                advent_of_code::y2024::day19::solve,
-                advent_of_code::y2024::day20::solve,
-                advent_of_code::y2024::day21::solve,
-                advent_of_code::y2024::day22::solve,
-                advent_of_code::y2024::day23::solve,
-                advent_of_code::y2024::day24::solve,
-                advent_of_code::y2024::day25::solve,
-            ],
-        ),
-    ]);
+    match args.command {
+        Some(Command::Serve { port, timeout }) => {
+            server::serve(port, Duration::from_secs(timeout)).expect("server failed");
+            return;
+        }
+        Some(Command::Solve { year, day }) => {
+            server::solve_stdin(year, day).expect("solving failed");
+            return;
+        }
+        Some(Command::Replay { year, day }) => {
+            let replay = year_days(year)
+                .into_iter()
+                .find(|d| d.day == day)
+                .expect("day not found")
+                .replay
+                .expect("day can't be replayed");
+            replay::play(replay).expect("replay failed");
+            return;
+        }
+        None => {}
+    }
+
+    if let Some(dir) = args.viz {
+        std::fs::create_dir_all(&dir).expect("failed to create frame directory");
+        viz::record_frames(dir);
+    }
 
     match (args.year, args.days) {
-        (None, _) => solvers
-            .iter()
-            .sorted()
-            .flat_map(|(_, ss)| ss)
-            .for_each(|s| {
-                s();
-            }),
-        (Some(year), days) if days.is_empty() => solvers
-            .get(&year)
-            .expect("year not found")
-            .iter()
-            .for_each(|s| {
-                s();
-            }),
+        (None, _) => DAYS.iter().for_each(|d| (d.solve)()),
+        (Some(year), days) if days.is_empty() => year_days(year).iter().for_each(|d| (d.solve)()),
         (Some(year), days) => {
-            let ss = solvers.get(&year).expect("year not found");
+            let ss = year_days(year);
             assert!(!days.contains(&0), "can't run day 0");
             assert!(*days.iter().max().unwrap() <= ss.len(), "day not found");
             days.iter().sorted().for_each(|d| {
-                ss[*d - 1]();
+                (ss[*d - 1].solve)();
             });
         }
     }
 }
+
+fn year_days(year: u32) -> Vec<&'static Day> {
+    let days = DAYS.iter().filter(|d| d.year == year).collect_vec();
+    assert!(!days.is_empty(), "year not found");
+    days
+}
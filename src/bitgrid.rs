@@ -0,0 +1,443 @@
+//! A grid of booleans packed into 64 bit words per row, for simulations which update every cell
+//! at once with a handful of bitwise operations per word.
+
+use crate::coordinate::{Bounds, Coordinate, DIAGONAL_DIRECTIONS};
+use std::fmt::{Display, Formatter};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+/// A fixed size grid of booleans with the origin in the top left. Cell `x` of a row is bit
+/// `x % 64` of word `x / 64`, and bits past the width are always zero.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    /// Words per row.
+    stride: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Creates a grid with all cells unset.
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            stride,
+            words: vec![0; stride * height],
+        }
+    }
+
+    /// Parses a rectangular block of characters, setting the cells for which `f` returns `true`.
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> bool) -> Self {
+        let width = s.lines().next().map_or(0, |l| l.chars().count());
+        let mut grid = Self::new(width, s.lines().count());
+        for (y, line) in s.lines().enumerate() {
+            assert_eq!(line.chars().count(), width, "ragged grid in line {y}");
+            for (x, c) in line.chars().enumerate() {
+                if f(c) {
+                    grid.words[y * grid.stride + x / 64] |= 1 << (x % 64);
+                }
+            }
+        }
+        grid
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    pub const fn bounds(&self) -> Bounds {
+        Bounds::from_size(self.width, self.height)
+    }
+
+    pub const fn contains(&self, c: Coordinate) -> bool {
+        self.bounds().contains(c)
+    }
+
+    /// Returns the word index and bit of a cell within the grid.
+    fn locate(&self, c: Coordinate) -> (usize, u64) {
+        assert!(self.contains(c), "{c} is outside the grid");
+        let (x, y) = (c.x as usize, c.y as usize);
+        (y * self.stride + x / 64, 1 << (x % 64))
+    }
+
+    /// Returns whether the cell at `c` is set, `false` outside the grid.
+    pub fn get(&self, c: Coordinate) -> bool {
+        self.contains(c) && {
+            let (word, bit) = self.locate(c);
+            self.words[word] & bit != 0
+        }
+    }
+
+    /// Sets or clears the cell at `c`. Panics outside the grid.
+    pub fn set(&mut self, c: Coordinate, value: bool) {
+        let (word, bit) = self.locate(c);
+        if value {
+            self.words[word] |= bit;
+        } else {
+            self.words[word] &= !bit;
+        }
+    }
+
+    /// Sets or clears every cell within `area`, ignoring the parts outside the grid.
+    pub fn set_area(&mut self, area: Bounds, value: bool) {
+        self.update_area(
+            area,
+            |word, mask| if value { word | mask } else { word & !mask },
+        );
+    }
+
+    /// Flips every cell within `area`, ignoring the parts outside the grid.
+    pub fn toggle_area(&mut self, area: Bounds) {
+        self.update_area(area, |word, mask| word ^ mask);
+    }
+
+    /// Replaces every word overlapping `area` with `f(word, mask)`, where `mask` selects the
+    /// cells of the word within the area.
+    fn update_area(&mut self, area: Bounds, f: impl Fn(u64, u64) -> u64) {
+        let clamp = |v: isize, max: usize| v.clamp(0, max as isize) as usize;
+        let (min_x, max_x) = (clamp(area.min.x, self.width), clamp(area.max.x, self.width));
+        let (min_y, max_y) = (
+            clamp(area.min.y, self.height),
+            clamp(area.max.y, self.height),
+        );
+        let masks: Vec<(usize, u64)> = (min_x / 64..max_x.div_ceil(64))
+            .map(|w| {
+                let from = min_x.max(w * 64) - w * 64;
+                let to = max_x.min(w * 64 + 64) - w * 64;
+                (w, low_bits(to) & !low_bits(from))
+            })
+            .collect();
+        let rows = self.words.chunks_exact_mut(self.stride.max(1));
+        for row in rows.take(max_y).skip(min_y) {
+            for &(w, mask) in &masks {
+                row[w] = f(row[w], mask);
+            }
+        }
+    }
+
+    /// Returns the words of row `y`.
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.stride..(y + 1) * self.stride]
+    }
+
+    /// Returns the number of set cells.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if no cell is set.
+    pub fn is_clear(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    /// Returns the coordinates of all set cells in row-major order.
+    pub fn ones(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.words.iter().enumerate().flat_map(move |(i, &word)| {
+            let (y, x) = (i / self.stride, i % self.stride * 64);
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    Coordinate::new((x + bit) as isize, y as isize)
+                })
+            })
+        })
+    }
+
+    /// Returns the grid with every cell moved by `offset`, dropping the cells moved outside.
+    pub fn shift(&self, offset: Coordinate) -> Self {
+        let mut shifted = Self::new(self.width, self.height);
+        for y in 0..self.height {
+            let target = y as isize + offset.y;
+            if (0..self.height as isize).contains(&target) {
+                let start = target as usize * self.stride;
+                shift_row(
+                    self.row(y),
+                    offset.x,
+                    &mut shifted.words[start..start + self.stride],
+                );
+            }
+        }
+        shifted.clear_padding();
+        shifted
+    }
+
+    /// Returns the grid with every cell moved by `offset`, wrapping around the edges.
+    pub fn rotate(&self, offset: Coordinate) -> Self {
+        let (dx, dy) = (
+            offset.x.rem_euclid(self.width.max(1) as isize),
+            offset.y.rem_euclid(self.height.max(1) as isize) as usize,
+        );
+        let mut rotated = Self::new(self.width, self.height);
+        let mut wrapped = vec![0; self.stride];
+        for y in 0..self.height {
+            let start = (y + dy) % self.height * self.stride;
+            let target = &mut rotated.words[start..start + self.stride];
+            shift_row(self.row(y), dx, target);
+            shift_row(self.row(y), dx - self.width as isize, &mut wrapped);
+            target.iter_mut().zip(&wrapped).for_each(|(t, w)| *t |= w);
+        }
+        rotated.clear_padding();
+        rotated
+    }
+
+    /// Counts the set neighbours, including diagonal ones, of every cell.
+    pub fn neighbour_counts(&self) -> NeighbourCounts {
+        let mut planes: [Self; 4] = std::array::from_fn(|_| Self::new(self.width, self.height));
+        for direction in DIAGONAL_DIRECTIONS {
+            let neighbours = self.shift(direction);
+            // Ripple-carry add the neighbours into the bit planes of the counts, word by word.
+            for (i, &neighbour) in neighbours.words.iter().enumerate() {
+                let mut carry = neighbour;
+                for plane in &mut planes {
+                    let sum = plane.words[i] ^ carry;
+                    carry &= plane.words[i];
+                    plane.words[i] = sum;
+                }
+            }
+        }
+        NeighbourCounts { planes }
+    }
+
+    /// Returns the next generation of Conway's Game of Life, treating cells outside as unset.
+    pub fn life(&self) -> Self {
+        let counts = self.neighbour_counts();
+        counts.equal_to(3) | &(self & &counts.equal_to(2))
+    }
+
+    /// Clears the bits past the width in the last word of every row.
+    fn clear_padding(&mut self) {
+        if !self.width.is_multiple_of(64) {
+            let mask = low_bits(self.width % 64);
+            self.words
+                .iter_mut()
+                .skip(self.stride - 1)
+                .step_by(self.stride)
+                .for_each(|w| *w &= mask);
+        }
+    }
+
+    fn zip_with(&mut self, other: &Self, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "grid sizes differ"
+        );
+        self.words
+            .iter_mut()
+            .zip(&other.words)
+            .for_each(|(a, &b)| *a = f(*a, b));
+    }
+}
+
+/// Returns a word with the lowest `n` bits set.
+const fn low_bits(n: usize) -> u64 {
+    if n >= 64 {
+        !0
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Writes `row` with every bit moved `dx` places towards higher indices into `out`, dropping the
+/// bits moved past either end.
+fn shift_row(row: &[u64], dx: isize, out: &mut [u64]) {
+    let (words, bits) = (dx.unsigned_abs() / 64, dx.unsigned_abs() % 64);
+    for (i, out) in out.iter_mut().enumerate() {
+        *out = if dx >= 0 {
+            let low = i.checked_sub(words).map_or(0, |j| row[j] << bits);
+            let carry = i
+                .checked_sub(words + 1)
+                .filter(|_| bits > 0)
+                .map_or(0, |j| row[j] >> (64 - bits));
+            low | carry
+        } else {
+            let high = row.get(i + words).map_or(0, |w| w >> bits);
+            let carry = row
+                .get(i + words + 1)
+                .filter(|_| bits > 0)
+                .map_or(0, |w| w << (64 - bits));
+            high | carry
+        };
+    }
+}
+
+/// The number of set neighbours of every cell, as bit planes of a binary number.
+#[derive(Clone, Debug)]
+pub struct NeighbourCounts {
+    planes: [BitGrid; 4],
+}
+
+impl NeighbourCounts {
+    /// Returns the cells with exactly `n` set neighbours.
+    pub fn equal_to(&self, n: u8) -> BitGrid {
+        let mut matches = BitGrid::new(self.planes[0].width, self.planes[0].height);
+        matches.words.fill(!0);
+        for (bit, plane) in self.planes.iter().enumerate() {
+            if n & (1 << bit) == 0 {
+                matches.zip_with(plane, |m, p| m & !p);
+            } else {
+                matches.zip_with(plane, |m, p| m & p);
+            }
+        }
+        matches.clear_padding();
+        matches
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                let c = Coordinate::new(x as isize, y as isize);
+                write!(f, "{}", if self.get(c) { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $method:ident, $assign_op:ident, $assign_method:ident) => {
+        impl $assign_op<&BitGrid> for BitGrid {
+            fn $assign_method(&mut self, rhs: &BitGrid) {
+                self.zip_with(rhs, |a, b| a.$method(b));
+            }
+        }
+
+        impl $op<&BitGrid> for BitGrid {
+            type Output = BitGrid;
+
+            fn $method(mut self, rhs: &BitGrid) -> BitGrid {
+                self.$assign_method(rhs);
+                self
+            }
+        }
+
+        impl $op for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, rhs: &BitGrid) -> BitGrid {
+                self.clone().$method(rhs)
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> BitGrid {
+        let mut inverted = self.clone();
+        inverted.words.iter_mut().for_each(|w| *w = !*w);
+        inverted.clear_padding();
+        inverted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLIDER: &str = "\
+.#....
+..#...
+###...
+......
+......";
+
+    #[test]
+    fn test_cells() {
+        let mut grid = BitGrid::new(130, 2);
+        grid.set(Coordinate::new(129, 1), true);
+        grid.set(Coordinate::new(64, 0), true);
+        assert!(grid.get(Coordinate::new(64, 0)) && !grid.get(Coordinate::new(63, 0)));
+        assert!(!grid.get(Coordinate::new(130, 1)));
+        assert_eq!(grid.row(1), [0, 0, 2]);
+        assert_eq!(
+            grid.ones().collect::<Vec<_>>(),
+            [Coordinate::new(64, 0), Coordinate::new(129, 1)]
+        );
+        grid.set(Coordinate::new(64, 0), false);
+        assert_eq!(grid.count_ones(), 1);
+    }
+
+    #[test]
+    fn test_areas() {
+        let mut grid = BitGrid::new(200, 3);
+        grid.set_area(Bounds::new(10..150, 0..2), true);
+        assert_eq!(grid.count_ones(), 280);
+        grid.toggle_area(Bounds::new(100..300, 1..5));
+        assert_eq!(grid.count_ones(), 140 + 140 + 100);
+        grid.set_area(Bounds::new(.., ..), false);
+        assert!(grid.is_clear());
+    }
+
+    #[test]
+    fn test_shift_and_rotate() {
+        let grid = BitGrid::parse(GLIDER, |c| c == '#');
+        let shifted = grid.shift(Coordinate::new(4, 3));
+        assert_eq!(
+            shifted.to_string(),
+            "......\n......\n......\n.....#\n......"
+        );
+        let rotated = grid.rotate(Coordinate::new(4, 3));
+        assert_eq!(
+            rotated.to_string(),
+            "#...##\n......\n......\n.....#\n#....."
+        );
+        assert_eq!(rotated.rotate(Coordinate::new(-4, -3)), grid);
+
+        // Across word boundaries in both directions.
+        let mut wide = BitGrid::new(150, 1);
+        wide.set(Coordinate::new(60, 0), true);
+        assert_eq!(
+            wide.shift(Coordinate::new(70, 0)).ones().next(),
+            Some((130, 0).into())
+        );
+        assert_eq!(
+            wide.rotate(Coordinate::new(100, 0)).ones().next(),
+            Some((10, 0).into())
+        );
+        assert_eq!(
+            wide.rotate(Coordinate::new(-70, 0)).ones().next(),
+            Some((140, 0).into())
+        );
+        assert!(wide.shift(Coordinate::new(90, 0)).is_clear());
+    }
+
+    #[test]
+    fn test_operators() {
+        let a = BitGrid::parse("##..", |c| c == '#');
+        let b = BitGrid::parse(".##.", |c| c == '#');
+        assert_eq!((&a & &b).to_string(), ".#..");
+        assert_eq!((&a | &b).to_string(), "###.");
+        assert_eq!((&a ^ &b).to_string(), "#.#.");
+        assert_eq!((!&a).to_string(), "..##");
+        assert_eq!((!&a).count_ones(), 2);
+    }
+
+    #[test]
+    fn test_life() {
+        let grid = BitGrid::parse(GLIDER, |c| c == '#');
+        let counts = grid.neighbour_counts();
+        assert!(counts.equal_to(5).get(Coordinate::new(1, 1)));
+        assert_eq!(counts.equal_to(0).count_ones(), 14);
+        let glided = (0..4).fold(grid.clone(), |g, _| g.life());
+        assert_eq!(glided, grid.shift(Coordinate::new(1, 1)));
+    }
+}
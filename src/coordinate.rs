@@ -1,11 +1,207 @@
 #![allow(dead_code)]
 
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
+use itertools::Itertools;
 use std::{
     fmt::{Debug, Display, Formatter},
     ops::{Add, AddAssign, Bound, RangeBounds, Sub, SubAssign},
+    str::FromStr,
 };
 
+/// A rectangular area with separate ranges for each axis, including `min` and excluding `max`.
+#[derive(Copy, Clone, Default, Debug, Hash, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Coordinate,
+    pub max: Coordinate,
+}
+
+impl Bounds {
+    /// Creates bounds from a range for each axis. Unbounded ends extend to the numeric limits, and
+    /// as the end is excluded, `isize::MAX` itself is never within bounds.
+    pub fn new(x: impl RangeBounds<isize>, y: impl RangeBounds<isize>) -> Self {
+        let (min_x, max_x) = half_open(x);
+        let (min_y, max_y) = half_open(y);
+        Self {
+            min: Coordinate::new(min_x, min_y),
+            max: Coordinate::new(max_x, max_y),
+        }
+    }
+
+    /// Returns the smallest bounds containing both `a` and `b`, e.g. two opposite corners.
+    pub fn spanning(a: Coordinate, b: Coordinate) -> Self {
+        Self::new(a.x.min(b.x)..=a.x.max(b.x), a.y.min(b.y)..=a.y.max(b.y))
+    }
+
+    /// Returns the smallest bounds containing all `coordinates`, if there are any.
+    pub fn enclosing(coordinates: impl IntoIterator<Item = Coordinate>) -> Option<Self> {
+        let mut coordinates = coordinates.into_iter();
+        let first = coordinates.next()?;
+        Some(coordinates.fold(Self::spanning(first, first), Self::including))
+    }
+
+    /// Returns the smallest bounds containing both these bounds and `c`.
+    pub fn including(self, c: Coordinate) -> Self {
+        Self {
+            min: Coordinate::new(self.min.x.min(c.x), self.min.y.min(c.y)),
+            max: Coordinate::new(
+                self.max.x.max(c.x.saturating_add(1)),
+                self.max.y.max(c.y.saturating_add(1)),
+            ),
+        }
+    }
+
+    /// The bounds of a `width` by `height` grid with the origin in the top left.
+    pub const fn from_size(width: usize, height: usize) -> Self {
+        Self {
+            min: Coordinate::new(0, 0),
+            max: Coordinate::new(width as isize, height as isize),
+        }
+    }
+
+    /// Returns the number of columns, zero if `max` isn't past `min`.
+    pub const fn width(&self) -> usize {
+        if self.max.x > self.min.x {
+            self.max.x.abs_diff(self.min.x)
+        } else {
+            0
+        }
+    }
+
+    /// Returns the number of rows, zero if `max` isn't past `min`.
+    pub const fn height(&self) -> usize {
+        if self.max.y > self.min.y {
+            self.max.y.abs_diff(self.min.y)
+        } else {
+            0
+        }
+    }
+
+    /// Returns `true` if `c` is within the bounds on both axes.
+    pub const fn contains(&self, c: Coordinate) -> bool {
+        self.min.x <= c.x && c.x < self.max.x && self.min.y <= c.y && c.y < self.max.y
+    }
+
+    /// Returns all coordinates within the bounds in row-major order, i.e. the filled rectangle.
+    pub fn coordinates(self) -> impl Iterator<Item = Coordinate> {
+        (self.min.y..self.max.y)
+            .flat_map(move |y| (self.min.x..self.max.x).map(move |x| Coordinate::new(x, y)))
+    }
+
+    /// Returns the coordinates along the inside edge of the bounds, i.e. the outlined rectangle,
+    /// clockwise from the top left.
+    pub fn outline(self) -> Box<dyn Iterator<Item = Coordinate>> {
+        let (width, height) = (self.width(), self.height());
+        if self.min.x >= self.max.x || self.min.y >= self.max.y {
+            return Box::new(std::iter::empty());
+        }
+        if width == 1 || height == 1 {
+            return Box::new(self.coordinates());
+        }
+        let (left, top, right, bottom) = (self.min.x, self.min.y, self.max.x - 1, self.max.y - 1);
+        let corners = [
+            (left, top),
+            (right, top),
+            (right, bottom),
+            (left, bottom),
+            (left, top),
+        ];
+        Box::new(polyline(corners.map(Coordinate::from)).take(2 * (width + height) - 4))
+    }
+
+    /// Wraps `c` around the edges on each axis independently, as if the area was a torus. Panics
+    /// if the bounds are empty, as there is nothing to wrap onto.
+    pub const fn wrap(&self, c: Coordinate) -> Coordinate {
+        self.wrap_wide(c.x as i128, c.y as i128)
+    }
+
+    /// Wraps a point which may be outside the range of [`Coordinate`], e.g. a sum of two.
+    const fn wrap_wide(&self, x: i128, y: i128) -> Coordinate {
+        assert!(
+            self.min.x < self.max.x && self.min.y < self.max.y,
+            "cannot wrap around empty bounds"
+        );
+        // The width may not fit an isize, but always fits an i128.
+        const fn wrap_axis(n: i128, min: isize, max: isize) -> isize {
+            let (min, max) = (min as i128, max as i128);
+            (min + (n - min).rem_euclid(max - min)) as isize
+        }
+        Coordinate {
+            x: wrap_axis(x, self.min.x, self.max.x),
+            y: wrap_axis(y, self.min.y, self.max.y),
+        }
+    }
+}
+
+/// Returns the coordinates of a horizontal, vertical or 45° diagonal segment, including both ends.
+/// Panics for any other angle, see [`line`] for those.
+pub fn segment(from: Coordinate, to: Coordinate) -> impl Iterator<Item = Coordinate> {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    assert!(
+        dx == 0 || dy == 0 || dx.abs() == dy.abs(),
+        "{from} to {to} is neither straight nor diagonal"
+    );
+    let (sx, sy) = (dx.signum(), dy.signum());
+    (0..=dx.abs().max(dy.abs())).map(move |i| Coordinate::new(from.x + sx * i, from.y + sy * i))
+}
+
+/// Returns the coordinates of a line at any angle using Bresenham's algorithm, including both
+/// ends. Straight and 45° lines come out the same as with [`segment`].
+pub fn line(from: Coordinate, to: Coordinate) -> impl Iterator<Item = Coordinate> {
+    let (dx, dy) = (
+        to.x.abs_diff(from.x) as isize,
+        -(to.y.abs_diff(from.y) as isize),
+    );
+    let (sx, sy) = ((to.x - from.x).signum(), (to.y - from.y).signum());
+    let mut error = dx + dy;
+    let mut next = Some(from);
+    std::iter::from_fn(move || {
+        let current = next?;
+        next = (current != to).then(|| {
+            let mut following = current;
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                following.x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                following.y += sy;
+            }
+            following
+        });
+        Some(current)
+    })
+}
+
+/// Returns the coordinates of lines connecting `points` in order, without repeating the shared
+/// ends.
+pub fn polyline(points: impl IntoIterator<Item = Coordinate>) -> impl Iterator<Item = Coordinate> {
+    let mut points = points.into_iter();
+    let first = points.next();
+    first.into_iter().chain(
+        points
+            .scan(first, |previous, point| {
+                Some(line(previous.replace(point)?, point).skip(1))
+            })
+            .flatten(),
+    )
+}
+
+/// Turns any range into inclusive start and exclusive end, clamped to the numeric limits.
+fn half_open(range: impl RangeBounds<isize>) -> (isize, isize) {
+    let start = match range.start_bound() {
+        Bound::Unbounded => isize::MIN,
+        Bound::Included(&x) => x,
+        Bound::Excluded(&x) => x.saturating_add(1),
+    };
+    let end = match range.end_bound() {
+        Bound::Unbounded => isize::MAX,
+        Bound::Included(&x) => x.saturating_add(1),
+        Bound::Excluded(&x) => x,
+    };
+    (start, end)
+}
+
 /// A two-dimensional coordinate, supporting negative coordinates.
 #[derive(Copy, Clone, Default, Hash, PartialEq, Eq, Ord, PartialOrd)]
 pub struct Coordinate {
@@ -91,19 +287,111 @@ impl Coordinate {
     }
 
     /// Returns `true` if `self` is within `range` on both axes. Inherently only works on square
-    /// grids.
+    /// grids, see [`Self::is_within`] for other shapes.
     pub fn is_in_bounds(self, range: impl RangeBounds<isize>) -> bool {
         range.contains(&self.x) && range.contains(&self.y)
     }
 
+    /// Addition that wraps around the edges of `bounds`, each axis independently.
+    pub const fn wrapping_add_within(self, rhs: Self, bounds: Bounds) -> Self {
+        bounds.wrap_wide(
+            self.x as i128 + rhs.x as i128,
+            self.y as i128 + rhs.y as i128,
+        )
+    }
+
+    /// Subtraction that wraps around the edges of `bounds`, each axis independently.
+    pub const fn wrapping_sub_within(self, rhs: Self, bounds: Bounds) -> Self {
+        bounds.wrap_wide(
+            self.x as i128 - rhs.x as i128,
+            self.y as i128 - rhs.y as i128,
+        )
+    }
+
+    /// Addition that fails if the result is outside `bounds`.
+    pub fn checked_add_within(self, rhs: Self, bounds: Bounds) -> Option<Self> {
+        let c = Self {
+            x: self.x.checked_add(rhs.x)?,
+            y: self.y.checked_add(rhs.y)?,
+        };
+        bounds.contains(c).then_some(c)
+    }
+
+    /// Subtraction that fails if the result is outside `bounds`.
+    pub fn checked_sub_within(self, rhs: Self, bounds: Bounds) -> Option<Self> {
+        let c = Self {
+            x: self.x.checked_sub(rhs.x)?,
+            y: self.y.checked_sub(rhs.y)?,
+        };
+        bounds.contains(c).then_some(c)
+    }
+
+    /// Returns `true` if `self` is within `bounds`.
+    pub const fn is_within(self, bounds: Bounds) -> bool {
+        bounds.contains(self)
+    }
+
     /// Returns the manhattan distance to `other`.
     pub const fn manhattan_distance(self, other: Self) -> usize {
         self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
     }
 
-    /// Returns the distance to `other`.
-    pub fn distance(self, other: Self) -> f32 {
-        (self.x.abs_diff(other.x) as f32 + self.y.abs_diff(other.y) as f32).sqrt()
+    /// Returns the Chebyshev distance to `other`, i.e. the number of king moves.
+    pub const fn chebyshev_distance(self, other: Self) -> usize {
+        let (dx, dy) = (self.x.abs_diff(other.x), self.y.abs_diff(other.y));
+        if dx > dy {
+            dx
+        } else {
+            dy
+        }
+    }
+
+    /// Returns the squared Euclidean distance to `other`, which is exact and enough for comparisons.
+    pub const fn squared_distance(self, other: Self) -> usize {
+        let (dx, dy) = (self.x.abs_diff(other.x), self.y.abs_diff(other.y));
+        dx * dx + dy * dy
+    }
+
+    /// Returns the Euclidean distance to `other`.
+    pub fn distance(self, other: Self) -> f64 {
+        (self.squared_distance(other) as f64).sqrt()
+    }
+
+    /// Returns all coordinates with a manhattan distance of at most `radius`, row by row.
+    pub fn within_manhattan_radius(self, radius: usize) -> impl Iterator<Item = Self> {
+        let r = radius as isize;
+        (-r..=r).flat_map(move |dy| {
+            let w = r - dy.abs();
+            (-w..=w).map(move |dx| Self::new(self.x + dx, self.y + dy))
+        })
+    }
+
+    /// Returns all coordinates with a manhattan distance of exactly `radius`, walking the four
+    /// sides of the diamond clockwise in step, starting from the right, bottom, left and top.
+    pub fn at_manhattan_radius(self, radius: usize) -> impl Iterator<Item = Self> {
+        let r = radius as isize;
+        let center = (radius == 0).then_some(self);
+        center.into_iter().chain((0..r).flat_map(move |i| {
+            [(r - i, i), (-i, r - i), (i - r, -i), (i, i - r)]
+                .map(|(dx, dy)| Self::new(self.x + dx, self.y + dy))
+        }))
+    }
+
+    /// Returns all coordinates with a Chebyshev distance of at most `radius`, row by row.
+    pub fn within_chebyshev_radius(self, radius: usize) -> impl Iterator<Item = Self> {
+        let r = radius as isize;
+        (-r..=r).flat_map(move |dy| (-r..=r).map(move |dx| Self::new(self.x + dx, self.y + dy)))
+    }
+
+    /// Returns all coordinates with a Chebyshev distance of exactly `radius`, walking the four
+    /// sides of the square clockwise in step, starting from the corners.
+    pub fn at_chebyshev_radius(self, radius: usize) -> impl Iterator<Item = Self> {
+        let r = radius as isize;
+        let center = (radius == 0).then_some(self);
+        center.into_iter().chain((0..2 * r).flat_map(move |i| {
+            [(i - r, -r), (r, i - r), (r - i, r), (-r, r - i)]
+                .map(|(dx, dy)| Self::new(self.x + dx, self.y + dy))
+        }))
     }
 
     /// Treating `self` as a relative coordinate, turns clockwise. Assumes X is right and Y is down.
@@ -123,6 +411,21 @@ impl Coordinate {
         }
     }
 
+    /// Treating `self` as a relative coordinate, applies `symmetry` around the origin.
+    pub const fn transform(self, symmetry: Symmetry) -> Self {
+        let (x, y) = (self.x, self.y);
+        match symmetry {
+            Symmetry::Identity => Self::new(x, y),
+            Symmetry::Rotate90 => Self::new(-y, x),
+            Symmetry::Rotate180 => Self::new(-x, -y),
+            Symmetry::Rotate270 => Self::new(y, -x),
+            Symmetry::FlipHorizontal => Self::new(-x, y),
+            Symmetry::FlipVertical => Self::new(x, -y),
+            Symmetry::Transpose => Self::new(y, x),
+            Symmetry::AntiTranspose => Self::new(-y, -x),
+        }
+    }
+
     /// Returns all neighbours (up, down, left, right) that are within bounds.
     pub fn neighbours(self, range: impl RangeBounds<isize> + Clone) -> Vec<Self> {
         DIRECTIONS
@@ -140,6 +443,22 @@ impl Coordinate {
             .filter_map(|(n, range)| self.checked_add(*n, range))
             .collect()
     }
+
+    /// Returns all neighbours (up, down, left, right) that are within `bounds`.
+    pub fn neighbours_within(self, bounds: Bounds) -> Vec<Self> {
+        DIRECTIONS
+            .iter()
+            .filter_map(|n| self.checked_add_within(*n, bounds))
+            .collect()
+    }
+
+    /// Returns all neighbours, including diagonal ones, that are within `bounds`.
+    pub fn diagonal_neighbours_within(self, bounds: Bounds) -> Vec<Self> {
+        DIAGONAL_DIRECTIONS
+            .iter()
+            .filter_map(|n| self.checked_add_within(*n, bounds))
+            .collect()
+    }
 }
 
 impl Add for Coordinate {
@@ -196,6 +515,246 @@ impl Debug for Coordinate {
     }
 }
 
+/// A coordinate in `D` dimensions, supporting negative coordinates.
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Ord, PartialOrd)]
+pub struct CoordinateN<const D: usize>(pub [isize; D]);
+
+/// A three-dimensional coordinate.
+pub type Coordinate3 = CoordinateN<3>;
+
+impl<const D: usize> CoordinateN<D> {
+    /// The unit steps along each axis, in both directions.
+    pub const FACE_DIRECTIONS: &'static [Self] = Self::FACE_TABLE.split_at(2 * D).0;
+
+    /// All steps to a neighbour, including diagonal ones.
+    pub const DIAGONAL_DIRECTIONS: &'static [Self] =
+        Self::DIAGONAL_TABLE.split_at(3_usize.pow(D as u32) - 1).0;
+
+    const FACE_TABLE: &'static [Self; MAX_NEIGHBOURS] = &face_directions();
+    const DIAGONAL_TABLE: &'static [Self; MAX_NEIGHBOURS] = &diagonal_directions();
+
+    pub const fn new(axes: [isize; D]) -> Self {
+        Self(axes)
+    }
+
+    /// Returns the manhattan distance to `other`.
+    pub fn manhattan_distance(self, other: Self) -> usize {
+        self.0.iter().zip(other.0).map(|(a, b)| a.abs_diff(b)).sum()
+    }
+
+    /// Addition that fails if the result is outside `bounds`.
+    pub fn checked_add_within(self, rhs: Self, bounds: BoundsN<D>) -> Option<Self> {
+        let mut axes = self.0;
+        for (a, b) in axes.iter_mut().zip(rhs.0) {
+            *a = a.checked_add(b)?;
+        }
+        let c = Self(axes);
+        bounds.contains(c).then_some(c)
+    }
+
+    /// Returns `true` if `self` is within `bounds`.
+    pub fn is_within(self, bounds: BoundsN<D>) -> bool {
+        bounds.contains(self)
+    }
+
+    /// Returns all neighbours sharing a face with `self`, two along each axis.
+    pub fn neighbours(self) -> Vec<Self> {
+        Self::FACE_DIRECTIONS.iter().map(|&d| self + d).collect()
+    }
+
+    /// Returns all neighbours, including those only sharing an edge or corner with `self`.
+    pub fn diagonal_neighbours(self) -> Vec<Self> {
+        Self::DIAGONAL_DIRECTIONS
+            .iter()
+            .map(|&d| self + d)
+            .collect()
+    }
+
+    /// Returns all neighbours sharing a face with `self` that are within `bounds`.
+    pub fn neighbours_within(self, bounds: BoundsN<D>) -> Vec<Self> {
+        Self::FACE_DIRECTIONS
+            .iter()
+            .filter_map(|&d| self.checked_add_within(d, bounds))
+            .collect()
+    }
+
+    /// Returns all neighbours, including diagonal ones, that are within `bounds`.
+    pub fn diagonal_neighbours_within(self, bounds: BoundsN<D>) -> Vec<Self> {
+        Self::DIAGONAL_DIRECTIONS
+            .iter()
+            .filter_map(|&d| self.checked_add_within(d, bounds))
+            .collect()
+    }
+}
+
+impl Coordinate3 {
+    pub const fn x(self) -> isize {
+        self.0[0]
+    }
+
+    pub const fn y(self) -> isize {
+        self.0[1]
+    }
+
+    pub const fn z(self) -> isize {
+        self.0[2]
+    }
+}
+
+/// The most dimensions neighbour steps are tabulated for.
+const MAX_DIMENSIONS: usize = 4;
+
+/// Room for the steps to every neighbour in [`MAX_DIMENSIONS`] dimensions.
+const MAX_NEIGHBOURS: usize = 3_usize.pow(MAX_DIMENSIONS as u32) - 1;
+
+/// The unit steps along each axis, in both directions, padded with zeros.
+const fn face_directions<const D: usize>() -> [CoordinateN<D>; MAX_NEIGHBOURS] {
+    assert!(D <= MAX_DIMENSIONS, "too many dimensions for neighbours");
+    let mut directions = [CoordinateN([0; D]); MAX_NEIGHBOURS];
+    let mut axis = 0;
+    while axis < D {
+        directions[2 * axis].0[axis] = -1;
+        directions[2 * axis + 1].0[axis] = 1;
+        axis += 1;
+    }
+    directions
+}
+
+/// All steps to a neighbour, including diagonal ones, padded with zeros.
+const fn diagonal_directions<const D: usize>() -> [CoordinateN<D>; MAX_NEIGHBOURS] {
+    assert!(D <= MAX_DIMENSIONS, "too many dimensions for neighbours");
+    let mut directions = [CoordinateN([0; D]); MAX_NEIGHBOURS];
+    let mut len = 0;
+    let mut n = 0;
+    while n < 3_usize.pow(D as u32) {
+        // Count in base 3, with the digits 0, 1 and 2 as steps -1, 0 and 1 along each axis.
+        let (mut digits, mut axis, mut moves) = (n, 0, false);
+        while axis < D {
+            let step = (digits % 3) as isize - 1;
+            directions[len].0[axis] = step;
+            moves |= step != 0;
+            digits /= 3;
+            axis += 1;
+        }
+        if moves {
+            len += 1;
+        }
+        n += 1;
+    }
+    directions
+}
+
+impl<const D: usize> Default for CoordinateN<D> {
+    fn default() -> Self {
+        Self([0; D])
+    }
+}
+
+impl<const D: usize> Add for CoordinateN<D> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<const D: usize> AddAssign for CoordinateN<D> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a += b);
+    }
+}
+
+impl<const D: usize> Sub for CoordinateN<D> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<const D: usize> SubAssign for CoordinateN<D> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0.iter_mut().zip(rhs.0).for_each(|(a, b)| *a -= b);
+    }
+}
+
+impl<const D: usize> From<[isize; D]> for CoordinateN<D> {
+    fn from(axes: [isize; D]) -> Self {
+        Self(axes)
+    }
+}
+
+impl<const D: usize> Display for CoordinateN<D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("({})", self.0.iter().join(",")))
+    }
+}
+
+impl<const D: usize> Debug for CoordinateN<D> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self::Display::fmt(&self, f)
+    }
+}
+
+/// A box in `D` dimensions, including `min` and excluding `max` on every axis.
+#[derive(Copy, Clone, Default, Debug, Hash, PartialEq, Eq)]
+pub struct BoundsN<const D: usize> {
+    pub min: CoordinateN<D>,
+    pub max: CoordinateN<D>,
+}
+
+/// A three-dimensional box.
+pub type Bounds3 = BoundsN<3>;
+
+impl<const D: usize> BoundsN<D> {
+    pub const fn new(min: CoordinateN<D>, max: CoordinateN<D>) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns the smallest bounds containing all `coordinates`, or `None` if there are none.
+    pub fn enclosing(coordinates: impl IntoIterator<Item = CoordinateN<D>>) -> Option<Self> {
+        let mut coordinates = coordinates.into_iter();
+        let first = coordinates.next()?;
+        let (min, max) = coordinates.fold((first, first), |(mut min, mut max), c| {
+            for axis in 0..D {
+                min.0[axis] = min.0[axis].min(c.0[axis]);
+                max.0[axis] = max.0[axis].max(c.0[axis]);
+            }
+            (min, max)
+        });
+        Some(Self::new(min, max + CoordinateN([1; D])))
+    }
+
+    /// Returns the bounds grown by `n` in every direction.
+    pub fn expand(self, n: isize) -> Self {
+        Self::new(
+            self.min - CoordinateN([n; D]),
+            self.max + CoordinateN([n; D]),
+        )
+    }
+
+    /// Returns `true` if `c` is within the bounds on every axis.
+    pub fn contains(&self, c: CoordinateN<D>) -> bool {
+        (0..D).all(|axis| self.min.0[axis] <= c.0[axis] && c.0[axis] < self.max.0[axis])
+    }
+
+    /// Returns all coordinates within the bounds.
+    pub fn coordinates(self) -> impl Iterator<Item = CoordinateN<D>> {
+        let sizes: [usize; D] =
+            std::array::from_fn(|axis| (self.max.0[axis] - self.min.0[axis]).max(0) as usize);
+        (0..sizes.iter().product()).map(move |mut n| {
+            let mut c = self.min;
+            for (axis, size) in sizes.iter().enumerate() {
+                c.0[axis] += (n % size) as isize;
+                n /= size;
+            }
+            c
+        })
+    }
+}
+
 /// A relative up coordinate, assuming origin in the top left.
 pub const UP: Coordinate = Coordinate::new(0, -1);
 /// A relative down coordinate, assuming origin in the top left.
@@ -220,10 +779,305 @@ pub const DIAGONAL_DIRECTIONS: [Coordinate; 8] = [
     Coordinate::new(1, 1),
 ];
 
+/// A heading on the grid, assuming origin in the top left. Variants are in clockwise order,
+/// starting from up.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction {
+    /// The four cardinal directions, clockwise starting from up.
+    pub const CARDINAL: [Self; 4] = [Self::Up, Self::Right, Self::Down, Self::Left];
+
+    /// All eight directions including diagonals, clockwise starting from up.
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// Returns the relative coordinate of one step in this direction.
+    pub const fn offset(self) -> Coordinate {
+        match self {
+            Self::Up => UP,
+            Self::UpRight => Coordinate::new(1, -1),
+            Self::Right => RIGHT,
+            Self::DownRight => Coordinate::new(1, 1),
+            Self::Down => DOWN,
+            Self::DownLeft => Coordinate::new(-1, 1),
+            Self::Left => LEFT,
+            Self::UpLeft => Coordinate::new(-1, -1),
+        }
+    }
+
+    /// Returns `true` for up, down, left and right.
+    pub const fn is_cardinal(self) -> bool {
+        (self as u8).is_multiple_of(2)
+    }
+
+    /// Turns 90° clockwise.
+    pub const fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Turns 90° counter-clockwise.
+    pub const fn turn_left(self) -> Self {
+        self.rotate(6)
+    }
+
+    /// Turns 180°.
+    pub const fn turn_around(self) -> Self {
+        self.rotate(4)
+    }
+
+    /// Turns 45° clockwise.
+    pub const fn turn_right_diagonal(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Turns 45° counter-clockwise.
+    pub const fn turn_left_diagonal(self) -> Self {
+        self.rotate(7)
+    }
+
+    /// Turns clockwise by `eighths` of a full turn.
+    const fn rotate(self, eighths: u8) -> Self {
+        Self::ALL[((self as u8 + eighths) % 8) as usize]
+    }
+}
+
+impl From<Direction> for Coordinate {
+    fn from(direction: Direction) -> Self {
+        direction.offset()
+    }
+}
+
+impl TryFrom<Coordinate> for Direction {
+    type Error = &'static str;
+
+    /// Converts a unit step, including diagonal ones, into its direction.
+    fn try_from(c: Coordinate) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|d| d.offset() == c)
+            .ok_or("not a unit step")
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = &'static str;
+
+    /// Parses arrows (`^v<>`), letters (`UDLR`) and compass points (`NSEW`).
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'U' | 'N' => Ok(Self::Up),
+            'v' | 'D' | 'S' => Ok(Self::Down),
+            '<' | 'L' | 'W' => Ok(Self::Left),
+            '>' | 'R' | 'E' => Ok(Self::Right),
+            _ => Err("invalid direction"),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = &'static str;
+
+    /// Parses single characters like [`Direction::try_from`], as well as two-letter compass points
+    /// like `NE` for diagonals.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NE" => Ok(Self::UpRight),
+            "SE" => Ok(Self::DownRight),
+            "SW" => Ok(Self::DownLeft),
+            "NW" => Ok(Self::UpLeft),
+            _ => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Self::try_from(c),
+                    _ => Err("invalid direction"),
+                }
+            }
+        }
+    }
+}
+
+/// One of the eight symmetries of the square. Rotations are clockwise, assuming X is right and Y
+/// is down.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Mirrors left and right.
+    FlipHorizontal,
+    /// Mirrors top and bottom.
+    FlipVertical,
+    /// Mirrors along the main diagonal, swapping X and Y.
+    Transpose,
+    /// Mirrors along the anti-diagonal.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Self; 8] = [
+        Self::Identity,
+        Self::Rotate90,
+        Self::Rotate180,
+        Self::Rotate270,
+        Self::FlipHorizontal,
+        Self::FlipVertical,
+        Self::Transpose,
+        Self::AntiTranspose,
+    ];
+
+    /// Returns the symmetry undoing this one.
+    pub const fn inverse(self) -> Self {
+        match self {
+            Self::Rotate90 => Self::Rotate270,
+            Self::Rotate270 => Self::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Returns `true` if this symmetry exchanges width and height.
+    pub const fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Self::Rotate90 | Self::Rotate270 | Self::Transpose | Self::AntiTranspose
+        )
+    }
+}
+
+/// A position along with the direction it's facing, for anything walking the grid.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Pose {
+    pub position: Coordinate,
+    pub direction: Direction,
+}
+
+impl Pose {
+    pub const fn new(position: Coordinate, direction: Direction) -> Self {
+        Self {
+            position,
+            direction,
+        }
+    }
+
+    /// Returns the position one step ahead.
+    pub fn ahead(self) -> Coordinate {
+        self.position + self.direction.offset()
+    }
+
+    /// Moves one step in the direction faced.
+    pub fn step(self) -> Self {
+        Self::new(self.ahead(), self.direction)
+    }
+
+    /// Turns 90° counter-clockwise on the spot.
+    pub const fn turn_left(self) -> Self {
+        Self::new(self.position, self.direction.turn_left())
+    }
+
+    /// Turns 90° clockwise on the spot.
+    pub const fn turn_right(self) -> Self {
+        Self::new(self.position, self.direction.turn_right())
+    }
+
+    /// Turns 180° on the spot.
+    pub const fn turn_around(self) -> Self {
+        Self::new(self.position, self.direction.turn_around())
+    }
+}
+
+/// The path taken by [`walk`] or [`walk_within`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Walk {
+    /// Every pose in order, starting with the initial one. Does not repeat the first repeated
+    /// pose when looping.
+    pub path: Vec<Pose>,
+    /// `true` if the walk ended by repeating a pose, `false` if it ended on its own.
+    pub looped: bool,
+}
+
+impl Walk {
+    /// Returns all distinct positions visited.
+    pub fn positions(&self) -> HashSet<Coordinate> {
+        self.path.iter().map(|p| p.position).collect()
+    }
+}
+
+/// Walks from `start`, asking `next` for each following pose, until it returns `None`, e.g. when
+/// leaving the map, or a pose repeats.
+pub fn walk(start: Pose, mut next: impl FnMut(Pose) -> Option<Pose>) -> Walk {
+    let mut seen = HashSet::from_iter([start]);
+    let mut path = vec![start];
+    let mut pose = start;
+    while let Some(following) = next(pose) {
+        if !seen.insert(following) {
+            return Walk { path, looped: true };
+        }
+        path.push(following);
+        pose = following;
+    }
+    Walk {
+        path,
+        looped: false,
+    }
+}
+
+/// Like [`walk`], but tracks poses in a dense table instead of hashing them, which is a lot faster
+/// for long walks. Panics if a pose leaves `bounds`.
+pub fn walk_within(
+    start: Pose,
+    bounds: Bounds,
+    mut next: impl FnMut(Pose) -> Option<Pose>,
+) -> Walk {
+    // One bit per direction for every position.
+    let index = |pose: Pose| {
+        assert!(bounds.contains(pose.position), "{pose:?} left the bounds");
+        let offset = pose.position - bounds.min;
+        (
+            offset.y as usize * bounds.width() + offset.x as usize,
+            1 << pose.direction as u8,
+        )
+    };
+    let mut seen = vec![0_u8; bounds.width() * bounds.height()];
+    let (idx, bit) = index(start);
+    seen[idx] |= bit;
+    let mut path = vec![start];
+    let mut pose = start;
+    while let Some(following) = next(pose) {
+        let (idx, bit) = index(following);
+        if seen[idx] & bit != 0 {
+            return Walk { path, looped: true };
+        }
+        seen[idx] |= bit;
+        path.push(following);
+        pose = following;
+    }
+    Walk {
+        path,
+        looped: false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ahash::HashSet;
 
     #[test]
     fn test_wrapping_add() {
@@ -256,4 +1110,382 @@ mod tests {
             HashSet::from_iter([(1, 2), (2, 1)].map(Coordinate::from))
         );
     }
+
+    #[test]
+    fn test_bounds() {
+        let bounds = Bounds::new(0..=10, -2..3);
+        assert_eq!(bounds, Bounds::new(0..11, -2..=2));
+        assert_eq!((bounds.width(), bounds.height()), (11, 5));
+        assert!(Coordinate::new(10, -2).is_within(bounds));
+        assert!(!Coordinate::new(10, 3).is_within(bounds));
+        assert!(!Coordinate::new(-1, 0).is_within(bounds));
+        assert_eq!(Bounds::from_size(101, 103), Bounds::new(0..101, 0..103));
+        assert_eq!(
+            Bounds::enclosing([(3, -1), (0, 2), (1, 1)].map(Coordinate::from)),
+            Some(Bounds::new(0..=3, -1..=2))
+        );
+        assert_eq!(Bounds::enclosing([]), None);
+        assert_eq!(
+            Bounds::from_size(2, 2).including(Coordinate::new(-1, 1)),
+            Bounds::new(-1..2, 0..2)
+        );
+
+        let inverted = Bounds {
+            min: Coordinate::new(5, 5),
+            max: Coordinate::new(0, 0),
+        };
+        assert_eq!((inverted.width(), inverted.height()), (0, 0));
+        assert_eq!(inverted.coordinates().count(), 0);
+        assert_eq!(Bounds::new(3..3, 0..1).width(), 0);
+    }
+
+    #[test]
+    fn test_wrapping_within() {
+        let bounds = Bounds::from_size(11, 7);
+        assert_eq!(
+            Coordinate::new(2, 4).wrapping_add_within(Coordinate::new(2, -3), bounds),
+            Coordinate::new(4, 1)
+        );
+        assert_eq!(
+            Coordinate::new(4, 1).wrapping_add_within(Coordinate::new(2, -3), bounds),
+            Coordinate::new(6, 5)
+        );
+        assert_eq!(
+            Coordinate::new(10, 6).wrapping_add_within(Coordinate::new(25, 15), bounds),
+            Coordinate::new(2, 0)
+        );
+        assert_eq!(
+            Coordinate::new(0, 0).wrapping_sub_within(Coordinate::new(1, 1), bounds),
+            Coordinate::new(10, 6)
+        );
+
+        let everything = Bounds::new(.., ..=isize::MAX);
+        assert_eq!(
+            everything,
+            Bounds::new(isize::MIN.., isize::MIN..isize::MAX)
+        );
+        assert_eq!(everything.width(), usize::MAX);
+        let c = Coordinate::new(isize::MIN, -5);
+        assert_eq!(everything.wrap(c), c);
+        assert_eq!(
+            Bounds::new(-1.., 0..1).wrap(Coordinate::new(-3, 4)),
+            Coordinate::new(isize::MAX - 2, 0)
+        );
+    }
+
+    #[test]
+    fn test_wrapping_within_extremes() {
+        let bounds = Bounds::from_size(10, 10);
+        assert_eq!(
+            Coordinate::new(isize::MAX, 0).wrapping_add_within(Coordinate::new(1, 0), bounds),
+            Coordinate::new(8, 0)
+        );
+        assert_eq!(
+            Coordinate::new(0, isize::MIN)
+                .wrapping_sub_within(Coordinate::new(0, isize::MAX), bounds),
+            Coordinate::new(0, 5)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot wrap around empty bounds")]
+    fn test_wrap_empty() {
+        Bounds::from_size(0, 3).wrap(Coordinate::new(1, 1));
+    }
+
+    #[test]
+    fn test_checked_within() {
+        let bounds = Bounds::from_size(3, 2);
+        assert_eq!(
+            Coordinate::new(1, 0).checked_add_within(Coordinate::new(1, 1), bounds),
+            Some(Coordinate::new(2, 1))
+        );
+        assert_eq!(
+            Coordinate::new(2, 0).checked_add_within(Coordinate::new(0, 2), bounds),
+            None
+        );
+        assert_eq!(
+            Coordinate::new(0, 1).checked_sub_within(RIGHT, bounds),
+            None
+        );
+    }
+
+    #[test]
+    fn test_distances() {
+        let (a, b) = (Coordinate::new(1, -1), Coordinate::new(4, 3));
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.chebyshev_distance(b), 4);
+        assert_eq!(a.squared_distance(b), 25);
+        assert_eq!(a.distance(b), 5.0);
+    }
+
+    #[test]
+    fn test_radius() {
+        let c = Coordinate::new(3, -2);
+        for r in 0..5 {
+            let within = c.within_manhattan_radius(r).collect::<HashSet<_>>();
+            assert_eq!(within.len(), 2 * r * r + 2 * r + 1);
+            assert!(within.iter().all(|&o| c.manhattan_distance(o) <= r));
+            let at = c.at_manhattan_radius(r).collect::<Vec<_>>();
+            assert_eq!(at.len(), (4 * r).max(1));
+            assert!(at.iter().all(|&o| c.manhattan_distance(o) == r));
+            assert_eq!(at.iter().collect::<HashSet<_>>().len(), at.len());
+
+            let within = c.within_chebyshev_radius(r).collect::<HashSet<_>>();
+            assert_eq!(within.len(), (2 * r + 1).pow(2));
+            assert!(within.iter().all(|&o| c.chebyshev_distance(o) <= r));
+            let at = c.at_chebyshev_radius(r).collect::<Vec<_>>();
+            assert_eq!(at.len(), (8 * r).max(1));
+            assert!(at.iter().all(|&o| c.chebyshev_distance(o) == r));
+            assert_eq!(at.iter().collect::<HashSet<_>>().len(), at.len());
+        }
+        assert_eq!(
+            Coordinate::default()
+                .at_manhattan_radius(1)
+                .collect::<Vec<_>>(),
+            [(1, 0), (0, 1), (-1, 0), (0, -1)].map(Coordinate::from)
+        );
+        assert_eq!(
+            Coordinate::default()
+                .at_chebyshev_radius(0)
+                .collect::<Vec<_>>(),
+            [Coordinate::default()]
+        );
+    }
+
+    #[test]
+    fn test_coordinate_n_arithmetic() {
+        let a = Coordinate3::new([1, -2, 3]);
+        let b = Coordinate3::new([-4, 5, 6]);
+        assert_eq!(a + b, Coordinate3::new([-3, 3, 9]));
+        assert_eq!(a - b, Coordinate3::new([5, -7, -3]));
+        assert_eq!(a.manhattan_distance(b), 5 + 7 + 3);
+        assert_eq!((a.x(), a.y(), a.z()), (1, -2, 3));
+        assert_eq!(a.to_string(), "(1,-2,3)");
+    }
+
+    #[test]
+    fn test_coordinate_n_neighbours() {
+        let origin = Coordinate3::default();
+        let neighbours = origin.neighbours();
+        assert_eq!(neighbours.len(), 6);
+        assert!(neighbours.iter().all(|n| n.manhattan_distance(origin) == 1));
+        assert_eq!(origin.diagonal_neighbours().len(), 26);
+        assert_eq!(CoordinateN::new([0; 4]).neighbours().len(), 8);
+        assert_eq!(CoordinateN::new([0; 4]).diagonal_neighbours().len(), 80);
+        assert!(!origin.diagonal_neighbours().contains(&origin));
+        assert_eq!(
+            CoordinateN::<2>::DIAGONAL_DIRECTIONS
+                .iter()
+                .map(|d| Coordinate::new(d.0[0], d.0[1]))
+                .collect::<HashSet<_>>(),
+            HashSet::from_iter(DIAGONAL_DIRECTIONS)
+        );
+        assert_eq!(
+            CoordinateN::<1>::FACE_DIRECTIONS,
+            [CoordinateN([-1]), CoordinateN([1])]
+        );
+    }
+
+    #[test]
+    fn test_coordinate_n_bounds() {
+        let bounds = Bounds3::enclosing([[1, 2, 3], [3, 2, 1]].map(Coordinate3::from)).unwrap();
+        assert_eq!(
+            bounds,
+            Bounds3::new(Coordinate3::new([1, 2, 1]), Coordinate3::new([4, 3, 4]))
+        );
+        assert_eq!(bounds.coordinates().count(), 9);
+        assert!(bounds.coordinates().all(|c| c.is_within(bounds)));
+        assert_eq!(bounds.expand(1).coordinates().count(), 5 * 3 * 5);
+        assert_eq!(
+            Coordinate3::new([1, 2, 1]).neighbours_within(bounds).len(),
+            2
+        );
+        assert_eq!(
+            Coordinate3::new([2, 2, 2])
+                .diagonal_neighbours_within(bounds)
+                .len(),
+            8
+        );
+        assert_eq!(Bounds3::enclosing([]), None);
+    }
+
+    #[test]
+    fn test_direction_turning() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.turn_right(), Direction::Up);
+        assert_eq!(Direction::DownLeft.turn_around(), Direction::UpRight);
+        assert_eq!(Direction::UpLeft.turn_right_diagonal(), Direction::Up);
+        assert_eq!(Direction::Up.turn_left_diagonal(), Direction::UpLeft);
+        for d in Direction::ALL {
+            assert_eq!(d.turn_right().offset(), d.offset().turn_clockwise());
+            assert_eq!(d.turn_left().offset(), d.offset().turn_counterclockwise());
+        }
+    }
+
+    #[test]
+    fn test_direction_conversion() {
+        for d in Direction::ALL {
+            assert_eq!(Direction::try_from(Coordinate::from(d)), Ok(d));
+        }
+        assert!(Direction::try_from(Coordinate::new(2, 0)).is_err());
+        assert_eq!(
+            HashSet::from_iter(Direction::CARDINAL.map(Coordinate::from)),
+            HashSet::from_iter(DIRECTIONS)
+        );
+        assert_eq!(
+            HashSet::from_iter(Direction::ALL.map(Coordinate::from)),
+            HashSet::from_iter(DIAGONAL_DIRECTIONS)
+        );
+        assert!(Direction::CARDINAL.iter().all(|d| d.is_cardinal()));
+    }
+
+    #[test]
+    fn test_direction_parsing() {
+        for (s, d) in [
+            ("^v<>", Direction::Up),
+            ("UDLR", Direction::Up),
+            ("NSWE", Direction::Up),
+        ] {
+            let parsed: Vec<_> = s.chars().map(|c| Direction::try_from(c).unwrap()).collect();
+            assert_eq!(parsed, [d, d.turn_around(), d.turn_left(), d.turn_right()]);
+        }
+        assert_eq!("NE".parse(), Ok(Direction::UpRight));
+        assert_eq!("W".parse(), Ok(Direction::Left));
+        assert!("X".parse::<Direction>().is_err());
+        assert!("NN".parse::<Direction>().is_err());
+    }
+
+    #[test]
+    fn test_symmetry() {
+        let c = Coordinate::new(2, 1);
+        assert_eq!(c.transform(Symmetry::Rotate90), c.turn_clockwise());
+        assert_eq!(c.transform(Symmetry::Rotate270), c.turn_counterclockwise());
+        assert_eq!(c.transform(Symmetry::Transpose), Coordinate::new(1, 2));
+        assert_eq!(
+            c.transform(Symmetry::FlipHorizontal),
+            Coordinate::new(-2, 1)
+        );
+        let images = Symmetry::ALL.map(|s| c.transform(s));
+        assert_eq!(images.iter().unique().count(), 8);
+        for s in Symmetry::ALL {
+            assert_eq!(c.transform(s).transform(s.inverse()), c);
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        let points =
+            |it: &mut dyn Iterator<Item = Coordinate>| it.map(|c| (c.x, c.y)).collect_vec();
+        let (a, b) = (Coordinate::new(1, 1), Coordinate::new(4, -2));
+        assert_eq!(
+            points(&mut segment(a, b)),
+            [(1, 1), (2, 0), (3, -1), (4, -2)]
+        );
+        assert_eq!(points(&mut line(a, b)), points(&mut segment(a, b)));
+        assert_eq!(points(&mut segment(b, b)), [(4, -2)]);
+        assert_eq!(
+            points(&mut line(Coordinate::new(0, 0), Coordinate::new(5, 2))),
+            [(0, 0), (1, 0), (2, 1), (3, 1), (4, 2), (5, 2)]
+        );
+        assert_eq!(
+            points(&mut line(Coordinate::new(2, 5), Coordinate::new(0, 0))).len(),
+            6
+        );
+        assert_eq!(
+            points(&mut polyline(
+                [(0, 0), (2, 0), (2, 1)].map(Coordinate::from)
+            )),
+            [(0, 0), (1, 0), (2, 0), (2, 1)]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_segment_angle() {
+        segment(Coordinate::new(0, 0), Coordinate::new(2, 1)).count();
+    }
+
+    #[test]
+    fn test_rectangles() {
+        let bounds = Bounds::spanning(Coordinate::new(2, 3), Coordinate::new(0, 1));
+        assert_eq!((bounds.width(), bounds.height()), (3, 3));
+        assert_eq!(bounds.coordinates().count(), 9);
+        assert_eq!(
+            bounds.outline().collect_vec(),
+            [
+                (0, 1),
+                (1, 1),
+                (2, 1),
+                (2, 2),
+                (2, 3),
+                (1, 3),
+                (0, 3),
+                (0, 2)
+            ]
+            .map(Coordinate::from)
+        );
+        assert_eq!(Bounds::from_size(4, 1).outline().count(), 4);
+        assert_eq!(Bounds::from_size(0, 3).outline().count(), 0);
+    }
+
+    #[test]
+    fn test_pose() {
+        let pose = Pose::new(Coordinate::new(1, 1), Direction::Up);
+        assert_eq!(pose.ahead(), Coordinate::new(1, 0));
+        assert_eq!(pose.step().position, Coordinate::new(1, 0));
+        assert_eq!(pose.turn_right().step().position, Coordinate::new(2, 1));
+        assert_eq!(pose.turn_left().step().position, Coordinate::new(0, 1));
+        assert_eq!(pose.turn_around().step().position, Coordinate::new(1, 2));
+        assert_eq!(pose.turn_left().turn_left(), pose.turn_around());
+    }
+
+    /// Walks forward, turning right at the edges of a 3x3 box.
+    fn circle(pose: Pose) -> Option<Pose> {
+        let bounds = Bounds::from_size(3, 3);
+        Some(if pose.ahead().is_within(bounds) {
+            pose.step()
+        } else {
+            pose.turn_right()
+        })
+    }
+
+    #[test]
+    fn test_walk() {
+        let start = Pose::new(Coordinate::new(0, 2), Direction::Up);
+        for walk in [
+            walk(start, circle),
+            walk_within(start, Bounds::from_size(3, 3), circle),
+        ] {
+            assert!(walk.looped);
+            // Eight moves along the edge and four turns in the corners.
+            assert_eq!(walk.path.len(), 12);
+            assert_eq!(walk.positions().len(), 8);
+        }
+
+        let straight = |pose: Pose| {
+            Some(pose.step()).filter(|p| p.position.is_within(Bounds::from_size(3, 3)))
+        };
+        let walk = walk(start, straight);
+        assert!(!walk.looped);
+        assert_eq!(
+            walk.path.last(),
+            Some(&Pose::new(Coordinate::new(0, 0), Direction::Up))
+        );
+    }
+
+    #[test]
+    fn test_neighbours_within() {
+        assert_eq!(
+            HashSet::from_iter(Coordinate::new(2, 0).neighbours_within(Bounds::from_size(3, 2))),
+            HashSet::from_iter([(1, 0), (2, 1)].map(Coordinate::from))
+        );
+        assert_eq!(
+            Coordinate::new(2, 0)
+                .diagonal_neighbours_within(Bounds::from_size(3, 2))
+                .len(),
+            3
+        );
+    }
 }
@@ -0,0 +1,171 @@
+//! Cycle detection for simulations which eventually repeat, to skip ahead to huge step counts.
+
+use ahash::HashMap;
+use std::hash::Hash;
+use std::ops::{Add, Mul, Sub};
+
+/// A repeating sequence of states: the state after `start` steps is the first one to come up
+/// again, `length` steps later.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// Returns the earliest step with the same state as step `n`.
+    pub const fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle in the states reached by repeatedly applying `step` to `initial`, using
+/// Brent's algorithm. Needs only a few states in memory at a time, but runs `step` up to about
+/// three times per state.
+pub fn brent<T: Clone + PartialEq>(initial: T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // Find the length by moving the hare ahead in growing powers of two until it meets the
+    // tortoise waiting at the last power.
+    let (mut power, mut length) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Then find the start by moving both one length apart until they meet.
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Steps `state` until `key` returns a value seen before, remembering every key. Use this when
+/// states can't be cloned or compared cheaply, or only part of the state matters. On return,
+/// `state` has been stepped `start + length` times.
+pub fn find_cycle<S, K: Hash + Eq>(
+    state: &mut S,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle {
+    let mut seen = HashMap::default();
+    let mut steps = 0;
+    loop {
+        if let Some(start) = seen.insert(key(state), steps) {
+            return Cycle {
+                start,
+                length: steps - start,
+            };
+        }
+        step(state);
+        steps += 1;
+    }
+}
+
+/// Returns the value of a metric after `target` steps, given its value after each step so far,
+/// starting with the initial state, assuming it changes by the same amount every cycle. `history`
+/// needs to cover at least the first repetition.
+pub fn extrapolate<M>(history: &[M], cycle: Cycle, target: usize) -> M
+where
+    M: Copy + Add<Output = M> + Sub<Output = M> + Mul<Output = M> + TryFrom<usize>,
+{
+    if let Some(&value) = history.get(target) {
+        return value;
+    }
+    let end = cycle.start + cycle.length;
+    assert!(history.len() > end, "history doesn't cover a whole cycle");
+    let gain = history[end] - history[cycle.start];
+    let cycles = M::try_from((target - cycle.start) / cycle.length)
+        .unwrap_or_else(|_| panic!("too many cycles to {target}"));
+    history[cycle.reduce(target)] + gain * cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2 -> ...
+    fn step(n: &u32) -> u32 {
+        if *n == 5 {
+            2
+        } else {
+            n + 1
+        }
+    }
+
+    #[test]
+    fn test_brent() {
+        assert_eq!(
+            brent(0, step),
+            Cycle {
+                start: 2,
+                length: 4
+            }
+        );
+        assert_eq!(
+            brent(3, step),
+            Cycle {
+                start: 0,
+                length: 4
+            }
+        );
+        assert_eq!(
+            brent(7, |_| 7),
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let mut state = 0;
+        let cycle = find_cycle(&mut state, |n| *n = step(n), |n| *n);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 2,
+                length: 4
+            }
+        );
+        assert_eq!(state, 2);
+        assert_eq!(cycle.reduce(1), 1);
+        assert_eq!(cycle.reduce(6), 2);
+        assert_eq!(cycle.reduce(13), 5);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // A metric which grows by 10 per cycle.
+        let history = [0_u64, 1, 3, 4, 6, 7, 13];
+        let cycle = Cycle {
+            start: 2,
+            length: 4,
+        };
+        assert_eq!(extrapolate(&history, cycle, 5), 7);
+        assert_eq!(extrapolate(&history, cycle, 10), 23);
+        assert_eq!(extrapolate(&history, cycle, 12), 26);
+        assert_eq!(
+            extrapolate(&history, cycle, 4_000_000_002),
+            3 + 10_000_000_000
+        );
+    }
+}
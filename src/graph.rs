@@ -0,0 +1,477 @@
+//! Graph algorithms. Clique searches work on undirected graphs given as a map from each node to
+//! its neighbours, distances and tours on an [`IndexedGraph`] or a matrix of edge weights.
+
+use ahash::{HashMap, HashSet};
+use std::collections::{BTreeSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// A directed, weighted graph storing nodes by dense index, so algorithms can use plain vectors
+/// and bitmasks instead of hashing nodes.
+#[derive(Clone, Debug)]
+pub struct IndexedGraph<N, W> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, W)>>,
+}
+
+impl<N, W> Default for IndexedGraph<N, W> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::default(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash, W: Copy> IndexedGraph<N, W> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of `node`, adding it if it's new.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&idx) = self.indices.get(&node) {
+            return idx;
+        }
+        self.nodes.push(node.clone());
+        self.edges.push(Vec::new());
+        self.indices.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// Adds an edge from `from` to `to`, adding the nodes as needed.
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.edges[from].push((to, weight));
+    }
+
+    /// Adds edges in both directions between `a` and `b`.
+    pub fn connect(&mut self, a: N, b: N, weight: W) {
+        self.add_edge(a.clone(), b.clone(), weight);
+        self.add_edge(b, a, weight);
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the index of `node`, if it's part of the graph.
+    pub fn index(&self, node: &N) -> Option<usize> {
+        self.indices.get(node).copied()
+    }
+
+    /// Returns all nodes, in index order.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Returns the outgoing edges of the node at `idx` as target index and weight.
+    pub fn edges(&self, idx: usize) -> &[(usize, W)] {
+        &self.edges[idx]
+    }
+
+    /// Returns the weight of the direct edge between every pair of nodes, if any. With multiple
+    /// edges between the same nodes the last one wins.
+    pub fn weights(&self) -> Vec<Vec<Option<W>>> {
+        let mut weights = vec![vec![None; self.len()]; self.len()];
+        for (from, edges) in self.edges.iter().enumerate() {
+            for &(to, weight) in edges {
+                weights[from][to] = Some(weight);
+            }
+        }
+        weights
+    }
+
+    /// Returns the shortest distance between every pair of nodes using Floyd–Warshall. Weights may
+    /// be negative, as long as there are no negative cycles.
+    pub fn distances(&self) -> Vec<Vec<Option<W>>>
+    where
+        W: Ord + Add<Output = W> + Default,
+    {
+        let mut distances = self.weights();
+        for (idx, row) in distances.iter_mut().enumerate() {
+            row[idx] = Some(row[idx].map_or(W::default(), |w| w.min(W::default())));
+        }
+        for via in 0..self.len() {
+            let onwards = distances[via].clone();
+            for row in &mut distances {
+                let Some(first) = row[via] else {
+                    continue;
+                };
+                for (distance, second) in row.iter_mut().zip(&onwards) {
+                    if let Some(second) = *second {
+                        if distance.is_none_or(|d| first + second < d) {
+                            *distance = Some(first + second);
+                        }
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    /// Returns the number of edges on the shortest route between every pair of nodes, ignoring
+    /// weights. Faster than [`Self::distances`] for sparse graphs.
+    pub fn bfs_distances(&self) -> Vec<Vec<Option<usize>>> {
+        (0..self.len())
+            .map(|start| {
+                let mut distances = vec![None; self.len()];
+                distances[start] = Some(0);
+                let mut queue = VecDeque::from([start]);
+                while let Some(node) = queue.pop_front() {
+                    let distance = distances[node].map(|d| d + 1);
+                    for &(next, _) in &self.edges[node] {
+                        if distances[next].is_none() {
+                            distances[next] = distance;
+                            queue.push_back(next);
+                        }
+                    }
+                }
+                distances
+            })
+            .collect()
+    }
+}
+
+/// Returns the cost of the cheapest route visiting every node exactly once, starting and ending
+/// anywhere, or `None` if there is no such route. `weights[a][b]` is the cost of going from `a`
+/// to `b`, if possible.
+pub fn shortest_hamiltonian_path<W>(weights: &[Vec<Option<W>>]) -> Option<W>
+where
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    held_karp(weights, false, Ord::min)
+}
+
+/// Like [`shortest_hamiltonian_path`], but for the most expensive route.
+pub fn longest_hamiltonian_path<W>(weights: &[Vec<Option<W>>]) -> Option<W>
+where
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    held_karp(weights, false, Ord::max)
+}
+
+/// Returns the cost of the cheapest round trip visiting every node exactly once, or `None` if
+/// there is no such trip.
+pub fn shortest_hamiltonian_cycle<W>(weights: &[Vec<Option<W>>]) -> Option<W>
+where
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    held_karp(weights, true, Ord::min)
+}
+
+/// Like [`shortest_hamiltonian_cycle`], but for the most expensive round trip.
+pub fn longest_hamiltonian_cycle<W>(weights: &[Vec<Option<W>>]) -> Option<W>
+where
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    held_karp(weights, true, Ord::max)
+}
+
+/// Held–Karp dynamic programming over subsets of visited nodes, keeping the best cost by `pick`
+/// for every subset and final node. Cycles all start at the first node, which is no loss as
+/// every cycle passes through it.
+fn held_karp<W>(weights: &[Vec<Option<W>>], cycle: bool, pick: fn(W, W) -> W) -> Option<W>
+where
+    W: Copy + Ord + Add<Output = W> + Default,
+{
+    let n = weights.len();
+    if n == 0 {
+        return None;
+    }
+    assert!(n < usize::BITS as usize, "too many nodes for Held–Karp");
+    let merge = |best: &mut Option<W>, cost: W| {
+        *best = Some(best.map_or(cost, |b| pick(b, cost)));
+    };
+    // The best cost of visiting the nodes in `mask`, ending at `last`.
+    let mut best = vec![vec![None; n]; 1 << n];
+    if cycle {
+        best[1][0] = Some(W::default());
+    } else {
+        for start in 0..n {
+            best[1 << start][start] = Some(W::default());
+        }
+    }
+    for mask in 1..1_usize << n {
+        for last in 0..n {
+            let Some(cost) = best[mask][last] else {
+                continue;
+            };
+            for next in (0..n).filter(|next| mask & 1 << next == 0) {
+                if let Some(weight) = weights[last][next] {
+                    merge(&mut best[mask | 1 << next][next], cost + weight);
+                }
+            }
+        }
+    }
+    let mut result = None;
+    for (last, cost) in best[(1 << n) - 1].iter().enumerate() {
+        let Some(cost) = *cost else {
+            continue;
+        };
+        if !cycle {
+            merge(&mut result, cost);
+        } else if let Some(weight) = weights[last][0] {
+            merge(&mut result, cost + weight);
+        }
+    }
+    result
+}
+
+/// Returns all nodes ordered so each has as few neighbours later in the order as possible, by
+/// repeatedly removing the node with the fewest remaining neighbours.
+pub fn degeneracy_order<N: Copy + Eq + Hash + Ord>(graph: &HashMap<N, HashSet<N>>) -> Vec<N> {
+    let mut degrees: HashMap<N, usize> = graph.iter().map(|(&n, vs)| (n, vs.len())).collect();
+    let mut queue: BTreeSet<(usize, N)> = degrees.iter().map(|(&n, &d)| (d, n)).collect();
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some((_, node)) = queue.pop_first() {
+        degrees.remove(&node);
+        for neighbour in &graph[&node] {
+            if let Some(degree) = degrees.get_mut(neighbour) {
+                queue.remove(&(*degree, *neighbour));
+                *degree -= 1;
+                queue.insert((*degree, *neighbour));
+            }
+        }
+        order.push(node);
+    }
+    order
+}
+
+/// Returns every maximal clique, i.e. every set of mutually connected nodes which can't be
+/// extended, using Bron–Kerbosch with pivoting in degeneracy order.
+pub fn maximal_cliques<N: Copy + Eq + Hash + Ord>(graph: &HashMap<N, HashSet<N>>) -> Vec<Vec<N>> {
+    let order = degeneracy_order(graph);
+    let position: HashMap<N, usize> = order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    let mut cliques = Vec::new();
+    for (i, &node) in order.iter().enumerate() {
+        let (later, earlier) = graph[&node].iter().partition(|n| position[n] > i);
+        bron_kerbosch(graph, &mut vec![node], later, earlier, &mut cliques);
+    }
+    cliques
+}
+
+/// Returns the largest clique, sorted. Of several largest cliques, returns the lexicographically
+/// smallest.
+pub fn maximum_clique<N: Copy + Eq + Hash + Ord>(graph: &HashMap<N, HashSet<N>>) -> Vec<N> {
+    maximal_cliques(graph)
+        .into_iter()
+        .map(|mut clique| {
+            clique.sort_unstable();
+            clique
+        })
+        .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+        .unwrap_or_default()
+}
+
+/// Extends `clique` with `candidates` in every maximal way, skipping nodes in `excluded` which
+/// were already covered.
+fn bron_kerbosch<N: Copy + Eq + Hash>(
+    graph: &HashMap<N, HashSet<N>>,
+    clique: &mut Vec<N>,
+    mut candidates: HashSet<N>,
+    mut excluded: HashSet<N>,
+    cliques: &mut Vec<Vec<N>>,
+) {
+    // Any maximal clique contains the pivot or one of its non-neighbours, so only those need
+    // branching on.
+    let Some(pivot) = candidates
+        .iter()
+        .chain(&excluded)
+        .max_by_key(|n| candidates.intersection(&graph[n]).count())
+    else {
+        cliques.push(clique.clone());
+        return;
+    };
+    let branches: Vec<N> = candidates.difference(&graph[pivot]).copied().collect();
+    for node in branches {
+        let neighbours = &graph[&node];
+        clique.push(node);
+        bron_kerbosch(
+            graph,
+            clique,
+            candidates.intersection(neighbours).copied().collect(),
+            excluded.intersection(neighbours).copied().collect(),
+            cliques,
+        );
+        clique.pop();
+        candidates.remove(&node);
+        excluded.insert(node);
+    }
+}
+
+/// Returns every clique of exactly `k` nodes, each sorted.
+pub fn cliques<N: Copy + Eq + Hash + Ord>(graph: &HashMap<N, HashSet<N>>, k: usize) -> Vec<Vec<N>> {
+    let order = degeneracy_order(graph);
+    let position: HashMap<N, usize> = order.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+    // Only extend with nodes later in the order, so every clique is found once, from its first
+    // node.
+    let later = |node: &N| -> Vec<N> {
+        graph[node]
+            .iter()
+            .filter(|n| position[n] > position[node])
+            .copied()
+            .collect()
+    };
+    let mut cliques = Vec::new();
+    let mut stack: Vec<(Vec<N>, Vec<N>)> = order.iter().map(|n| (vec![*n], later(n))).collect();
+    while let Some((mut clique, candidates)) = stack.pop() {
+        if clique.len() >= k {
+            if clique.len() == k {
+                clique.sort_unstable();
+                cliques.push(clique);
+            }
+            continue;
+        }
+        for (i, node) in candidates.iter().enumerate() {
+            let neighbours = &graph[node];
+            let mut extended = clique.clone();
+            extended.push(*node);
+            stack.push((
+                extended,
+                candidates[i + 1..]
+                    .iter()
+                    .filter(|n| neighbours.contains(n))
+                    .copied()
+                    .collect(),
+            ));
+        }
+    }
+    cliques
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    /// Two triangles sharing the edge 2-3, a square 3-4-5-6 and a lone node 7.
+    fn graph() -> HashMap<u8, HashSet<u8>> {
+        let edges = [
+            (1, 2),
+            (1, 3),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+            (4, 5),
+            (5, 6),
+            (6, 3),
+        ];
+        let mut graph: HashMap<u8, HashSet<u8>> = HashMap::default();
+        for (a, b) in edges {
+            graph.entry(a).or_default().insert(b);
+            graph.entry(b).or_default().insert(a);
+        }
+        graph.entry(7).or_default();
+        graph
+    }
+
+    /// The example from 2015 day 9, with an extra, far away town.
+    fn towns() -> IndexedGraph<&'static str, u32> {
+        let mut graph = IndexedGraph::new();
+        graph.connect("London", "Dublin", 464);
+        graph.connect("London", "Belfast", 518);
+        graph.connect("Dublin", "Belfast", 141);
+        graph.connect("Belfast", "Paris", 1000);
+        graph
+    }
+
+    #[test]
+    fn test_indexed_graph() {
+        let graph = towns();
+        assert_eq!(graph.len(), 4);
+        assert_eq!(graph.index(&"Belfast"), Some(2));
+        assert_eq!(graph.nodes()[1], "Dublin");
+        assert_eq!(graph.edges(3), &[(2, 1000)]);
+        assert_eq!(graph.weights()[0][3], None);
+    }
+
+    #[test]
+    fn test_distances() {
+        let graph = towns();
+        let distances = graph.distances();
+        assert_eq!(distances[0][0], Some(0));
+        assert_eq!(distances[0][2], Some(518));
+        assert_eq!(distances[3][1], Some(1141));
+        let hops = graph.bfs_distances();
+        assert_eq!(hops[0][3], Some(2));
+        assert_eq!(hops[3][3], Some(0));
+
+        let mut directed = IndexedGraph::new();
+        directed.add_edge('a', 'b', 1);
+        directed.add_edge('b', 'c', -3);
+        directed.add_node('d');
+        assert_eq!(
+            directed.distances()[0],
+            vec![Some(0), Some(1), Some(-2), None]
+        );
+        assert_eq!(directed.distances()[2][0], None);
+    }
+
+    #[test]
+    fn test_hamiltonian() {
+        let weights = towns().weights();
+        assert_eq!(shortest_hamiltonian_path(&weights), Some(1605));
+        assert_eq!(longest_hamiltonian_path(&weights), Some(1982));
+        // Paris is a dead end, so there is no way back.
+        assert_eq!(shortest_hamiltonian_cycle(&weights), None);
+
+        let triangle: Vec<_> = weights[..3].iter().map(|row| row[..3].to_vec()).collect();
+        assert_eq!(shortest_hamiltonian_path(&triangle), Some(605));
+        assert_eq!(longest_hamiltonian_path(&triangle), Some(982));
+        assert_eq!(shortest_hamiltonian_cycle(&triangle), Some(1123));
+        assert_eq!(longest_hamiltonian_cycle(&triangle), Some(1123));
+    }
+
+    #[test]
+    fn test_degeneracy_order() {
+        let order = degeneracy_order(&graph());
+        assert_eq!(order.len(), 7);
+        assert_eq!(order[0], 7);
+    }
+
+    #[test]
+    fn test_maximal_cliques() {
+        let cliques = maximal_cliques(&graph())
+            .into_iter()
+            .map(|c| c.into_iter().sorted().collect_vec())
+            .sorted()
+            .collect_vec();
+        assert_eq!(
+            cliques,
+            vec![
+                vec![1, 2, 3],
+                vec![2, 3, 4],
+                vec![3, 6],
+                vec![4, 5],
+                vec![5, 6],
+                vec![7]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_maximum_clique() {
+        // Both 1-2-3 and 2-3-4 are largest, the smaller one wins regardless of hashing.
+        assert_eq!(maximum_clique(&graph()), [1, 2, 3]);
+        assert_eq!(maximum_clique::<u8>(&HashMap::default()), []);
+    }
+
+    #[test]
+    fn test_cliques() {
+        let graph = graph();
+        assert_eq!(cliques(&graph, 1).len(), 7);
+        assert_eq!(cliques(&graph, 2).len(), 8);
+        assert_eq!(
+            cliques(&graph, 3).into_iter().sorted().collect_vec(),
+            vec![vec![1, 2, 3], vec![2, 3, 4]]
+        );
+        assert!(cliques(&graph, 4).is_empty());
+    }
+}
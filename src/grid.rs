@@ -0,0 +1,364 @@
+use crate::coordinate::{Bounds, Coordinate, Symmetry, DIAGONAL_DIRECTIONS, DIRECTIONS};
+use std::{
+    fmt::{Display, Formatter},
+    ops::{Index, IndexMut},
+};
+
+/// A dense, rectangular grid with the origin in the top left, indexed by [`Coordinate`].
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Returns a copy of the grid with `symmetry` applied, keeping the origin in the top left.
+    pub fn transform(&self, symmetry: Symmetry) -> Self {
+        let (width, height) = if symmetry.swaps_axes() {
+            (self.height, self.width)
+        } else {
+            (self.width, self.height)
+        };
+        // Opposite corners stay opposite, so their images give the new top left.
+        let far = Coordinate::new(self.width as isize - 1, self.height as isize - 1);
+        let (a, b) = (
+            Coordinate::default().transform(symmetry),
+            far.transform(symmetry),
+        );
+        let offset = Coordinate::new(a.x.min(b.x), a.y.min(b.y));
+        let inverse = symmetry.inverse();
+        let cells = Bounds::from_size(width, height)
+            .coordinates()
+            .map(|c| self[(c + offset).transform(inverse)].clone())
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T: Clone + PartialEq> Grid<T> {
+    /// Returns the grid under every symmetry of the square, skipping duplicates.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations: Vec<Self> = Vec::with_capacity(8);
+        for symmetry in Symmetry::ALL {
+            let grid = self.transform(symmetry);
+            if !orientations.contains(&grid) {
+                orientations.push(grid);
+            }
+        }
+        orientations
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Returns `true` if `pattern` matches with its top left at `at`. `None` cells in the pattern
+    /// match anything.
+    pub fn matches_at(&self, pattern: &Grid<Option<T>>, at: Coordinate) -> bool {
+        pattern.iter().all(|(c, expected)| {
+            expected
+                .as_ref()
+                .is_none_or(|e| self.get(at + c) == Some(e))
+        })
+    }
+
+    /// Returns the top left corners of all places `pattern` matches.
+    pub fn matches<'a>(
+        &'a self,
+        pattern: &'a Grid<Option<T>>,
+    ) -> impl Iterator<Item = Coordinate> + 'a {
+        self.coordinates()
+            .filter(move |&at| self.matches_at(pattern, at))
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parses a string using rows and columns as coordinates, mapping each character to a cell.
+    /// Panics if the lines are not all the same length.
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> T) -> Self {
+        let width = s.lines().next().map_or(0, |l| l.chars().count());
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(s.len());
+        for line in s.lines() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            assert_eq!(cells.len() - before, width, "ragged grid in line {height}");
+            height += 1;
+        }
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Builds a grid from cells in row-major order. Panics if `cells` does not fill whole rows.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            cells.len().is_multiple_of(width),
+            "cells don't fit the width"
+        );
+        let height = cells.len().checked_div(width).unwrap_or(0);
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the area covered by the grid.
+    pub const fn bounds(&self) -> Bounds {
+        Bounds::from_size(self.width, self.height)
+    }
+
+    /// Returns `true` if `c` lies within the grid.
+    pub const fn contains(&self, c: Coordinate) -> bool {
+        self.bounds().contains(c)
+    }
+
+    pub fn get(&self, c: Coordinate) -> Option<&T> {
+        self.index_of(c).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_mut(&mut self, c: Coordinate) -> Option<&mut T> {
+        self.index_of(c).map(|idx| &mut self.cells[idx])
+    }
+
+    /// Returns all neighbours (up, down, left, right) that are within the grid.
+    pub fn neighbours(&self, c: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        DIRECTIONS
+            .iter()
+            .map(move |&d| c + d)
+            .filter(|&n| self.contains(n))
+    }
+
+    /// Returns all neighbours, including diagonal ones, that are within the grid.
+    pub fn diagonal_neighbours(&self, c: Coordinate) -> impl Iterator<Item = Coordinate> + '_ {
+        DIAGONAL_DIRECTIONS
+            .iter()
+            .map(move |&d| c + d)
+            .filter(|&n| self.contains(n))
+    }
+
+    /// Returns the row at `y`, left to right.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Returns all rows, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Returns the column at `x`, top to bottom.
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// Returns all columns, left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Returns all coordinates in row-major order.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> {
+        let width = self.width;
+        (0..self.cells.len())
+            .map(move |idx| Coordinate::new((idx % width) as isize, (idx / width) as isize))
+    }
+
+    /// Returns all cells along with their coordinates, in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, &T)> {
+        self.coordinates().zip(self.cells.iter())
+    }
+
+    /// Returns the first coordinate in row-major order whose cell matches `predicate`.
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<Coordinate> {
+        self.positions(move |t| predicate(t)).next()
+    }
+
+    /// Returns all coordinates whose cells match `predicate`.
+    pub fn positions<'a>(
+        &'a self,
+        mut predicate: impl FnMut(&T) -> bool + 'a,
+    ) -> impl Iterator<Item = Coordinate> + 'a {
+        self.iter()
+            .filter(move |(_, t)| predicate(t))
+            .map(|(c, _)| c)
+    }
+
+    /// Returns a grid of the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn index_of(&self, c: Coordinate) -> Option<usize> {
+        self.contains(c)
+            .then(|| c.y as usize * self.width + c.x as usize)
+    }
+}
+
+impl<T> Index<Coordinate> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, c: Coordinate) -> &Self::Output {
+        self.get(c)
+            .unwrap_or_else(|| panic!("{c} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Coordinate> for Grid<T> {
+    fn index_mut(&mut self, c: Coordinate) -> &mut Self::Output {
+        self.get_mut(c)
+            .unwrap_or_else(|| panic!("{c} is outside the grid"))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+#..
+.#.
+..#
+#..";
+
+    fn grid() -> Grid<char> {
+        Grid::parse(INPUT, |c| c)
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid[Coordinate::new(1, 1)], '#');
+        assert_eq!(grid[Coordinate::new(2, 1)], '.');
+        assert_eq!(grid.get(Coordinate::new(3, 0)), None);
+        assert_eq!(grid.get(Coordinate::new(0, -1)), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_parse_ragged() {
+        Grid::parse("..\n.", |c| c);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+        assert_eq!(
+            grid.neighbours(Coordinate::new(0, 0)).collect::<Vec<_>>(),
+            vec![Coordinate::new(0, 1), Coordinate::new(1, 0)]
+        );
+        assert_eq!(grid.neighbours(Coordinate::new(1, 1)).count(), 4);
+        assert_eq!(grid.diagonal_neighbours(Coordinate::new(2, 3)).count(), 3);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = grid();
+        assert_eq!(grid.row(3), &['#', '.', '.']);
+        assert_eq!(grid.rows().count(), 4);
+        assert_eq!(grid.column(0).collect::<String>(), "#..#".to_string());
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.rev().collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["#..#", "..#.", ".#.."]
+        );
+    }
+
+    #[test]
+    fn test_find_and_positions() {
+        let grid = grid();
+        assert_eq!(grid.find(|&c| c == '#'), Some(Coordinate::new(0, 0)));
+        assert_eq!(grid.find(|&c| c == 'x'), None);
+        assert_eq!(
+            grid.positions(|&c| c == '#').collect::<Vec<_>>(),
+            [(0, 0), (1, 1), (2, 2), (0, 3)].map(Coordinate::from)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(grid().to_string(), INPUT);
+        assert_eq!(
+            grid().map(|&c| u8::from(c == '#')).to_string(),
+            "100\n010\n001\n100"
+        );
+    }
+
+    #[test]
+    fn test_transform() {
+        let grid = Grid::from_vec(3, vec![1, 2, 3, 4, 5, 6]);
+        let shown = |s| grid.transform(s).to_string();
+        assert_eq!(shown(Symmetry::Identity), "123\n456");
+        assert_eq!(shown(Symmetry::Rotate90), "41\n52\n63");
+        assert_eq!(shown(Symmetry::Rotate180), "654\n321");
+        assert_eq!(shown(Symmetry::Rotate270), "36\n25\n14");
+        assert_eq!(shown(Symmetry::FlipHorizontal), "321\n654");
+        assert_eq!(shown(Symmetry::FlipVertical), "456\n123");
+        assert_eq!(shown(Symmetry::Transpose), "14\n25\n36");
+        assert_eq!(shown(Symmetry::AntiTranspose), "63\n52\n41");
+        assert_eq!(grid.orientations().len(), 8);
+        assert_eq!(Grid::new(2, 2, 0).orientations().len(), 1);
+    }
+
+    #[test]
+    fn test_matches() {
+        let pattern = Grid::parse("#.\n.#", |c| (c == '#').then_some('#'));
+        assert_eq!(
+            grid().matches(&pattern).collect::<Vec<_>>(),
+            [(0, 0), (1, 1)].map(Coordinate::from)
+        );
+        assert!(!grid().matches_at(&pattern, Coordinate::new(2, 2)));
+    }
+
+    #[test]
+    fn test_from_vec() {
+        let mut grid = Grid::from_vec(2, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(grid.height(), 3);
+        grid[Coordinate::new(1, 2)] = 0;
+        assert_eq!(grid.to_string(), "12\n34\n50");
+    }
+}
@@ -0,0 +1,119 @@
+//! Maps names from the input to small, dense ids, so solvers can index vectors and set bits
+//! instead of hashing strings over and over.
+
+use ahash::HashMap;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// An integer type usable as interned id.
+pub trait Id: Copy + Eq + Hash + Ord + Debug {
+    /// Converts a dense index into an id. Panics if it doesn't fit.
+    fn from_index(index: usize) -> Self;
+
+    /// Returns the dense index of this id.
+    fn index(self) -> usize;
+}
+
+macro_rules! impl_id {
+    ($($t:ty),+) => {$(
+        impl Id for $t {
+            fn from_index(index: usize) -> Self {
+                Self::try_from(index).expect(concat!("too many names for ", stringify!($t), " ids"))
+            }
+
+            fn index(self) -> usize {
+                self as usize
+            }
+        }
+    )+};
+}
+
+impl_id!(u8, u16, u32, usize);
+
+/// Hands out ids in order of first appearance, starting from zero, borrowing the names.
+#[derive(Clone, Debug)]
+pub struct Interner<'a, I = u32> {
+    ids: HashMap<&'a str, I>,
+    names: Vec<&'a str>,
+}
+
+impl<I> Default for Interner<'_, I> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::default(),
+            names: Vec::new(),
+        }
+    }
+}
+
+impl<'a, I: Id> Interner<'a, I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `name`, assigning the next free one if it's new.
+    pub fn intern(&mut self, name: &'a str) -> I {
+        *self.ids.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            I::from_index(self.names.len() - 1)
+        })
+    }
+
+    /// Returns the id of `name`, if it was interned.
+    pub fn get(&self, name: &str) -> Option<I> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the name for `id`. Panics if the id wasn't handed out by this interner.
+    pub fn name(&self, id: I) -> &'a str {
+        self.names[id.index()]
+    }
+
+    /// Returns all names, indexed by id.
+    pub fn names(&self) -> &[&'a str] {
+        &self.names
+    }
+
+    /// Returns all ids handed out so far.
+    pub fn ids(&self) -> impl Iterator<Item = I> {
+        (0..self.names.len()).map(I::from_index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interner() {
+        let mut interner: Interner<u16> = Interner::new();
+        assert!(interner.is_empty());
+        assert_eq!(interner.intern("AA"), 0);
+        assert_eq!(interner.intern("BB"), 1);
+        assert_eq!(interner.intern("AA"), 0);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.get("BB"), Some(1));
+        assert_eq!(interner.get("CC"), None);
+        assert_eq!(interner.name(1), "BB");
+        assert_eq!(interner.names(), ["AA", "BB"]);
+        assert_eq!(interner.ids().collect::<Vec<_>>(), [0, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow() {
+        let names: Vec<String> = (0..=256).map(|i| i.to_string()).collect();
+        let mut interner: Interner<u8> = Interner::new();
+        for name in &names {
+            interner.intern(name);
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Sets of integers stored as ranges, for puzzles where the ranges are too large to enumerate.
+
+use std::ops::Range;
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent half-open ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    ranges: Vec<Range<i64>>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the ranges making up the set, in ascending order.
+    pub fn ranges(&self) -> &[Range<i64>] {
+        &self.ranges
+    }
+
+    /// Returns the number of integers in the set.
+    pub fn len(&self) -> u64 {
+        self.ranges.iter().map(|r| r.start.abs_diff(r.end)).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Returns the smallest integer in the set.
+    pub fn min(&self) -> Option<i64> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    /// Returns the largest integer in the set.
+    pub fn max(&self) -> Option<i64> {
+        self.ranges.last().map(|r| r.end - 1)
+    }
+
+    /// Returns the index of the first range ending after `x`.
+    fn position(&self, x: i64) -> usize {
+        self.ranges.partition_point(|r| r.end <= x)
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        self.ranges
+            .get(self.position(x))
+            .is_some_and(|r| r.start <= x)
+    }
+
+    /// Returns `true` if every integer in `range` is in the set.
+    pub fn contains_range(&self, range: Range<i64>) -> bool {
+        range.is_empty()
+            || self
+                .ranges
+                .get(self.position(range.start))
+                .is_some_and(|r| r.start <= range.start && range.end <= r.end)
+    }
+
+    /// Returns `true` if any integer in `range` is in the set.
+    pub fn overlaps(&self, range: Range<i64>) -> bool {
+        !range.is_empty()
+            && self
+                .ranges
+                .get(self.position(range.start))
+                .is_some_and(|r| r.start < range.end)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.ranges.iter().all(|r| other.contains_range(r.clone()))
+    }
+
+    /// Adds all integers in `range`, merging it with the ranges it overlaps or touches.
+    pub fn insert(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    /// Removes all integers in `range`, splitting the ranges it cuts through.
+    pub fn remove(&mut self, range: Range<i64>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.position(range.start);
+        let last = self.ranges.partition_point(|r| r.start < range.end);
+        if first >= last {
+            return;
+        }
+        let before = self.ranges[first].start..range.start;
+        let after = range.end..self.ranges[last - 1].end;
+        let kept = [before, after].into_iter().filter(|r| !r.is_empty());
+        self.ranges.splice(first..last, kept);
+    }
+
+    /// Returns all integers in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        other.ranges.iter().for_each(|r| union.insert(r.clone()));
+        union
+    }
+
+    /// Returns all integers in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut a, mut b) = (
+            self.ranges.iter().peekable(),
+            other.ranges.iter().peekable(),
+        );
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let overlap = x.start.max(y.start)..x.end.min(y.end);
+            if !overlap.is_empty() {
+                ranges.push(overlap);
+            }
+            if x.end < y.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+        Self { ranges }
+    }
+
+    /// Returns all integers in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        other
+            .ranges
+            .iter()
+            .for_each(|r| difference.remove(r.clone()));
+        difference
+    }
+
+    /// Splits the set into the integers below `x` and the ones from `x` on.
+    pub fn split_at(&self, x: i64) -> (Self, Self) {
+        let i = self.position(x);
+        let mut below = self.ranges[..i].to_vec();
+        let mut above = self.ranges[i..].to_vec();
+        if let Some(straddling) = above.first_mut().filter(|r| r.start < x) {
+            below.push(straddling.start..x);
+            straddling.start = x;
+        }
+        (Self { ranges: below }, Self { ranges: above })
+    }
+}
+
+impl From<Range<i64>> for IntervalSet {
+    fn from(range: Range<i64>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl FromIterator<Range<i64>> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Range<i64>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|r| set.insert(r));
+        set
+    }
+}
+
+/// A piecewise-linear mapping which shifts integers in each source range by its offset, and
+/// leaves all other integers unchanged.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OffsetMap {
+    /// Disjoint source ranges with their offsets, sorted by start.
+    pieces: Vec<(Range<i64>, i64)>,
+}
+
+impl OffsetMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shifts all integers in `source` by `offset`. Panics if `source` overlaps an earlier one.
+    pub fn insert(&mut self, source: Range<i64>, offset: i64) {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= source.start);
+        assert!(
+            self.pieces
+                .get(i)
+                .is_none_or(|(r, _)| source.end <= r.start),
+            "overlapping source ranges"
+        );
+        self.pieces.insert(i, (source, offset));
+    }
+
+    /// Maps a single integer.
+    pub fn get(&self, x: i64) -> i64 {
+        let i = self.pieces.partition_point(|(r, _)| r.end <= x);
+        match self.pieces.get(i) {
+            Some((r, offset)) if r.start <= x => x + offset,
+            _ => x,
+        }
+    }
+
+    /// Maps every integer in `set`, splitting its ranges wherever the offset changes.
+    pub fn apply(&self, set: &IntervalSet) -> IntervalSet {
+        let mut mapped = IntervalSet::new();
+        for range in set.ranges() {
+            let mut start = range.start;
+            let first = self.pieces.partition_point(|(r, _)| r.end <= start);
+            for (source, offset) in &self.pieces[first..] {
+                if source.start >= range.end {
+                    break;
+                }
+                mapped.insert(start..source.start);
+                let end = source.end.min(range.end);
+                mapped.insert(start.max(source.start) + offset..end + offset);
+                start = end;
+            }
+            mapped.insert(start..range.end);
+        }
+        mapped
+    }
+
+    /// Returns every integer which maps into `set`, i.e. the inverse of `apply`.
+    pub fn preimage(&self, set: &IntervalSet) -> IntervalSet {
+        let sources: IntervalSet = self.pieces.iter().map(|(r, _)| r.clone()).collect();
+        let mut preimage = set.difference(&sources);
+        for (source, offset) in &self.pieces {
+            let shifted: IntervalSet = set
+                .ranges()
+                .iter()
+                .map(|r| r.start - offset..r.end - offset)
+                .collect();
+            let mapped = shifted.intersection(&IntervalSet::from(source.clone()));
+            preimage = preimage.union(&mapped);
+        }
+        preimage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set: IntervalSet = [0..3, 10..12, 5..7].into_iter().collect();
+        assert_eq!(set.ranges(), [0..3, 5..7, 10..12]);
+        set.insert(3..5);
+        assert_eq!(set.ranges(), [0..7, 10..12]);
+        set.insert(6..11);
+        assert_eq!(set, IntervalSet::from(0..12));
+        set.remove(2..4);
+        set.remove(8..8);
+        set.remove(11..20);
+        assert_eq!(set.ranges(), [0..2, 4..11]);
+        assert_eq!(set.len(), 9);
+        assert_eq!((set.min(), set.max()), (Some(0), Some(10)));
+        assert!(set.contains(4) && !set.contains(2) && !set.contains(11));
+        assert!(set.contains_range(5..11) && !set.contains_range(1..5));
+        assert!(set.overlaps(1..5) && !set.overlaps(2..4));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: IntervalSet = [0..5, 10..15].into_iter().collect();
+        let b: IntervalSet = [3..12, 20..25].into_iter().collect();
+        assert_eq!(a.union(&b).ranges(), [0..15, 20..25]);
+        assert_eq!(a.intersection(&b).ranges(), [3..5, 10..12]);
+        assert_eq!(a.difference(&b).ranges(), [0..3, 12..15]);
+        assert!(IntervalSet::from(11..13).is_subset(&a));
+        assert!(!b.is_subset(&a));
+
+        let (below, above) = a.split_at(12);
+        assert_eq!(below.ranges(), [0..5, 10..12]);
+        assert_eq!(above, IntervalSet::from(12..15));
+        let (below, above) = a.split_at(7);
+        assert_eq!(below, IntervalSet::from(0..5));
+        assert_eq!(above, IntervalSet::from(10..15));
+    }
+
+    #[test]
+    fn test_offset_map() {
+        let mut map = OffsetMap::new();
+        map.insert(50..98, 2);
+        map.insert(98..100, -48);
+        assert_eq!(
+            [0, 49, 50, 97, 98, 99, 100].map(|x| map.get(x)),
+            [0, 49, 52, 99, 50, 51, 100]
+        );
+        let set: IntervalSet = [45..60, 97..99].into_iter().collect();
+        assert_eq!(map.apply(&set).ranges(), [45..51, 52..62, 99..100]);
+        assert_eq!(
+            map.apply(&IntervalSet::from(0..200)),
+            IntervalSet::from(0..200)
+        );
+
+        let preimage = map.preimage(&[10..20, 50..53].into_iter().collect());
+        assert_eq!(preimage.ranges(), [10..20, 50..51, 98..100]);
+        assert_eq!(map.apply(&preimage).ranges(), [10..20, 50..53]);
+    }
+}
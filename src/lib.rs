@@ -1,6 +1,22 @@
 use std::{fmt::Display, time::Instant};
 
+pub mod bitgrid;
 pub mod coordinate;
+pub mod cycle;
+pub mod graph;
+pub mod grid;
+pub mod interner;
+pub mod interval;
+pub mod math;
+pub mod memo;
+pub mod parsing;
+pub mod pathfinding;
+pub mod polygon;
+pub mod replay;
+pub mod server;
+pub mod sparsegrid;
+pub mod unionfind;
+pub mod viz;
 
 pub mod y2015;
 pub mod y2022;
@@ -12,16 +28,134 @@ macro_rules! solve {
     ($day:expr) => {
         pub fn solve() {
             let start = std::time::Instant::now();
-            let input = parse(include_str!(concat!("inputs/", $day)));
-            let a = part_1(&input);
-            let b = part_2(&input);
+            let (a, b) = run(include_str!(concat!("inputs/", $day)));
             println!("Day {}:     ({:>3} ms)", $day, start.elapsed().as_millis());
             println!("{:>20}", a);
             println!("{:>20}", b);
         }
+
+        pub fn run(input: &str) -> (String, String) {
+            let input = parse(input);
+            (part_1(&input).to_string(), part_2(&input).to_string())
+        }
+    };
+}
+
+/// Solves both parts for a raw puzzle input, returning the formatted answers.
+pub type Solver = fn(&str) -> (String, String);
+
+/// A registered puzzle solution.
+pub struct Day {
+    pub year: u32,
+    pub day: u32,
+    /// Solves the bundled input and prints the results.
+    pub solve: fn(),
+    /// Solves an arbitrary input.
+    pub run: Solver,
+    /// Simulates the bundled input step by step, for days which are simulations.
+    pub replay: Option<replay::Replay>,
+}
+
+macro_rules! day {
+    ($year:literal, $day:literal, $($module:ident)::+, replay) => {
+        Day {
+            replay: Some($($module)::+::replay),
+            ..day!($year, $day, $($module)::+)
+        }
+    };
+    ($year:literal, $day:literal, $($module:ident)::+) => {
+        Day {
+            year: $year,
+            day: $day,
+            solve: $($module)::+::solve,
+            run: $($module)::+::run,
+            replay: None,
+        }
     };
 }
 
+/// All solutions, ordered by year and day.
+pub const DAYS: &[Day] = &[
+    day!(2015, 1, y2015::day_01),
+    day!(2015, 2, y2015::day_02),
+    day!(2015, 3, y2015::day_03),
+    day!(2015, 4, y2015::day_04),
+    day!(2015, 5, y2015::day_05),
+    day!(2015, 6, y2015::day_06),
+    day!(2015, 7, y2015::day_07),
+    day!(2015, 8, y2015::day_08),
+    day!(2015, 9, y2015::day_09),
+    day!(2015, 10, y2015::day_10),
+    day!(2015, 11, y2015::day_11),
+    day!(2015, 12, y2015::day_12),
+    day!(2015, 13, y2015::day_13),
+    day!(2015, 14, y2015::day_14),
+    day!(2015, 15, y2015::day_15),
+    day!(2015, 16, y2015::day_16),
+    day!(2015, 17, y2015::day_17),
+    day!(2015, 18, y2015::day_18),
+    day!(2022, 1, y2022::day01),
+    day!(2022, 2, y2022::day02),
+    day!(2022, 3, y2022::day03),
+    day!(2022, 4, y2022::day04),
+    day!(2022, 5, y2022::day05),
+    day!(2022, 6, y2022::day06),
+    day!(2022, 7, y2022::day07),
+    day!(2022, 8, y2022::day08),
+    day!(2022, 9, y2022::day09, replay),
+    day!(2022, 10, y2022::day10),
+    day!(2022, 11, y2022::day11),
+    day!(2022, 12, y2022::day12),
+    day!(2022, 13, y2022::day13),
+    day!(2022, 14, y2022::day14, replay),
+    day!(2022, 15, y2022::day15),
+    day!(2022, 16, y2022::day16),
+    day!(2022, 17, y2022::day17, replay),
+    day!(2022, 18, y2022::day18),
+    day!(2022, 19, y2022::day19),
+    day!(2022, 20, y2022::day20),
+    day!(2022, 21, y2022::day21),
+    day!(2022, 22, y2022::day22),
+    day!(2022, 23, y2022::day23, replay),
+    day!(2022, 24, y2022::day24, replay),
+    day!(2022, 25, y2022::day25),
+    day!(2023, 1, y2023::day_01),
+    day!(2023, 2, y2023::day_02),
+    day!(2023, 3, y2023::day_03),
+    day!(2023, 4, y2023::day_04),
+    day!(2023, 5, y2023::day_05),
+    day!(2023, 6, y2023::day_06),
+    day!(2023, 7, y2023::day_07),
+    day!(2023, 8, y2023::day_08),
+    day!(2023, 9, y2023::day_09),
+    day!(2023, 10, y2023::day_10),
+    day!(2024, 1, y2024::day01),
+    day!(2024, 2, y2024::day02),
+    day!(2024, 3, y2024::day03),
+    day!(2024, 4, y2024::day04),
+    day!(2024, 5, y2024::day05),
+    day!(2024, 6, y2024::day06, replay),
+    day!(2024, 7, y2024::day07),
+    day!(2024, 8, y2024::day08),
+    day!(2024, 9, y2024::day09),
+    day!(2024, 10, y2024::day10),
+    day!(2024, 11, y2024::day11),
+    day!(2024, 12, y2024::day12),
+    day!(2024, 13, y2024::day13),
+    day!(2024, 14, y2024::day14, replay),
+    day!(2024, 15, y2024::day15, replay),
+    day!(2024, 16, y2024::day16),
+    day!(2024, 17, y2024::day17),
+    day!(2024, 18, y2024::day18),
+    day!(2024, 19, y2024::day19),
+    day!(2024, 20, y2024::day20),
+    day!(2024, 21, y2024::day21),
+    day!(2024, 22, y2024::day22),
+    day!(2024, 23, y2024::day23),
+    day!(2024, 24, y2024::day24),
+    day!(2024, 25, y2024::day25),
+];
+
 /// Prints results in standardized way, optionally also capturing timing data.
 pub fn print_results<A, B>(year: u32, day: u32, part_1: A, part_2: B, started_at: Option<Instant>)
 where
@@ -0,0 +1,146 @@
+//! Number theory helpers, mostly for combining periodic behaviour.
+
+/// Greatest common divisor, with `gcd(0, 0) == 0`.
+pub const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, with `lcm(n, 0) == 0`. Panics on overflow.
+pub const fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    match (a / gcd(a, b)).checked_mul(b) {
+        Some(lcm) => lcm,
+        None => panic!("lcm overflows"),
+    }
+}
+
+/// Least common multiple of all `numbers`, 1 if there are none.
+pub fn lcm_all(numbers: impl IntoIterator<Item = u64>) -> u64 {
+    numbers.into_iter().fold(1, lcm)
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y == g == gcd(a, b)`, with `g` not negative.
+pub const fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Returns `x` in `0..modulus` such that `a * x ≡ 1`, if `a` and `modulus` are coprime.
+pub const fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    assert!(modulus > 0, "modulus must be positive");
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g == 1 {
+        Some(x.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+/// Returns `base` to the power of `exponent`, modulo `modulus`.
+pub const fn mod_pow(base: u64, mut exponent: u64, modulus: u64) -> u64 {
+    assert!(modulus > 0, "modulus must be positive");
+    let modulus = modulus as u128;
+    let mut base = base as u128 % modulus;
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// Solves a system of congruences `x ≡ residue (mod modulus)` with the Chinese remainder
+/// theorem. The moduli don't need to be coprime. Returns `(x, m)` with `x` in `0..m`, where `m`
+/// is the least common multiple of all moduli, so every `x + k * m` is a solution. Returns
+/// `None` if the congruences contradict each other. Panics if `m` doesn't fit.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    congruences
+        .into_iter()
+        .try_fold((0, 1), |(x, m), (residue, modulus)| {
+            assert!(modulus > 0, "modulus must be positive");
+            // Find k with x + k * m ≡ residue (mod modulus).
+            let (g, inverse, _) = extended_gcd(m, modulus);
+            let difference = residue as i128 - x as i128;
+            if difference % g as i128 != 0 {
+                return None;
+            }
+            let step = modulus / g;
+            let k = (difference / g as i128) % step as i128 * inverse as i128 % step as i128;
+            let lcm = m.checked_mul(step).expect("crt modulus overflows");
+            let x = (x as i128 + k * m as i128).rem_euclid(lcm as i128);
+            Some((x as i64, lcm))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+        assert_eq!(lcm_all([2, 3, 4, 5]), 60);
+        assert_eq!(lcm_all([]), 1);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240, 46), (-240, 46), (7, -3), (0, 5), (5, 0)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i64);
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_pow(4, 13, 497), 445);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(-1, 4), (1, 6)]), Some((7, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        assert_eq!(
+            crt([(0, 1_000_000_007), (1, 998_244_353)]),
+            Some((993_328_913_953_302_349, 998_244_359_987_710_471))
+        );
+        // Residues at the extremes, with differences which don't fit an i64.
+        assert_eq!(crt([(i64::MIN, 5), (i64::MAX, 7)]), Some((7, 35)));
+        assert_eq!(
+            crt([(i64::MAX, 1 << 61), (i64::MIN, 3)]),
+            Some(((1 << 61) - 1, 3 << 61))
+        );
+    }
+}
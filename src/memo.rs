@@ -0,0 +1,92 @@
+//! Memoization with caches owned by the caller, so they can borrow from the input and are dropped
+//! with it, unlike the process-wide caches of `#[memoize]`.
+
+use ahash::HashMap;
+use std::hash::Hash;
+
+/// A cache of computed values, usually threaded through a recursive function.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    values: HashMap<K, V>,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key`, or computes and caches it with `f`. `f` gets the memo
+    /// back to look up the values it depends on.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.values.get(&key) {
+            return value.clone();
+        }
+        let value = f(self);
+        self.values.insert(key, value.clone());
+        value
+    }
+
+    /// Returns the cached value for `key`, if it was computed.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.values.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        memo.get_or_insert_with(n, |memo| match n {
+            0 | 1 => n,
+            _ => fibonacci(memo, n - 1) + fibonacci(memo, n - 2),
+        })
+    }
+
+    #[test]
+    fn test_recursion() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(&mut memo, 90), 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 91);
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut calls = 0;
+        let mut memo = Memo::new();
+        assert_eq!(memo.get_or_insert_with("a", |_| 1), 1);
+        assert_eq!(
+            memo.get_or_insert_with("a", |_| {
+                calls += 1;
+                2
+            }),
+            1
+        );
+        assert_eq!(calls, 0);
+        assert_eq!(memo.get(&"a"), Some(&1));
+        assert_eq!(memo.len(), 1);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get_or_insert_with("a", |_| 2), 2);
+    }
+}
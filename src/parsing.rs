@@ -0,0 +1,184 @@
+//! Reusable nom parsers for the usual shapes of puzzle input. They collect [`VerboseError`]s, so
+//! [`parse_all`] can point at the line and column where parsing failed, and say what it was
+//! parsing at the time.
+
+use crate::coordinate::Coordinate;
+use crate::grid::Grid;
+use nom::{
+    bytes::complete::{is_not, tag},
+    character::complete::{digit1, line_ending, multispace0, one_of, space1},
+    combinator::{all_consuming, map, map_opt, map_res, not, opt, recognize},
+    error::{context, convert_error, VerboseError},
+    multi::separated_list1,
+    sequence::{pair, preceded, separated_pair, terminated},
+    Finish, IResult, Parser,
+};
+use std::str::FromStr;
+
+/// The result of the parsers in this module. Use it for puzzle parsers composing them, too.
+pub type PResult<'a, O> = IResult<&'a str, O, VerboseError<&'a str>>;
+
+/// Parses all of `input` with `parser`, apart from trailing whitespace. Panics with the position
+/// and context of the error if that fails.
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    parser: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> O {
+    match all_consuming(terminated(parser, multispace0))(input).finish() {
+        Ok((_, output)) => output,
+        Err(error) => panic!("invalid input:\n{}", convert_error(input, error)),
+    }
+}
+
+/// An integer with an optional sign, e.g. `-12`, of any type implementing `FromStr`.
+pub fn number<T: FromStr>(s: &str) -> PResult<'_, T> {
+    context(
+        "number",
+        map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse),
+    )(s)
+}
+
+/// Numbers separated by `separator`, e.g. `1,2,3` with `char(',')`.
+pub fn numbers<'a, T: FromStr, S>(
+    separator: impl Parser<&'a str, S, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<T>> {
+    separated_list1(separator, number)
+}
+
+/// Numbers separated by spaces or tabs, e.g. `79 14 55 13`.
+pub fn spaced_numbers<T: FromStr>(s: &str) -> PResult<'_, Vec<T>> {
+    numbers(space1)(s)
+}
+
+/// A coordinate made of two numbers separated by `separator`, e.g. `0,4` with `char(',')`.
+pub fn coordinate<'a, S>(
+    separator: impl Parser<&'a str, S, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Coordinate> {
+    context(
+        "coordinate",
+        map(separated_pair(number, separator, number), |(x, y)| {
+            Coordinate::new(x, y)
+        }),
+    )
+}
+
+/// A `key: value` field, e.g. `Register A: 729`.
+pub fn field<'a, O>(
+    key: &'static str,
+    value: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, O> {
+    context(key, preceded(pair(tag(key), tag(": ")), value))
+}
+
+/// One `item` per line, up to a blank line or the end.
+pub fn lines<'a, O>(
+    item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    committed_list(terminated(line_ending, not(line_ending)), item)
+}
+
+/// Blocks separated by blank lines, e.g. a list of `lines`.
+pub fn blocks<'a, O>(
+    block: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    committed_list(pair(line_ending, line_ending), block)
+}
+
+/// Like `separated_list1`, except that an item must follow every separator which isn't just
+/// trailing whitespace. Errors then point into the broken item, not at the end of the list.
+fn committed_list<'a, O, S>(
+    mut separator: impl Parser<&'a str, S, VerboseError<&'a str>>,
+    mut item: impl Parser<&'a str, O, VerboseError<&'a str>>,
+) -> impl FnMut(&'a str) -> PResult<'a, Vec<O>> {
+    move |s| {
+        let (mut s, first) = item.parse(s)?;
+        let mut items = vec![first];
+        while let Ok((rest, _)) = separator.parse(s) {
+            if rest.trim_start().is_empty() {
+                break;
+            }
+            let (rest, next) = item.parse(rest).map_err(|error| match error {
+                nom::Err::Error(error) => nom::Err::Failure(error),
+                error => error,
+            })?;
+            items.push(next);
+            s = rest;
+        }
+        Ok((s, items))
+    }
+}
+
+/// A rectangular grid of characters converted by `f`, up to a blank line or the end.
+pub fn grid<'a, T>(f: impl Fn(char) -> T) -> impl FnMut(&'a str) -> PResult<'a, Grid<T>> {
+    context(
+        "grid",
+        map_opt(
+            separated_list1(line_ending, is_not("\r\n")),
+            move |rows: Vec<&str>| {
+                let width = rows[0].chars().count();
+                rows.iter()
+                    .all(|row| row.chars().count() == width)
+                    .then(|| {
+                        Grid::from_vec(width, rows.iter().flat_map(|r| r.chars()).map(&f).collect())
+                    })
+            },
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::{alpha1, char};
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(number::<i64>("-12 rest"), Ok((" rest", -12)));
+        assert_eq!(number::<u8>("+7"), Ok(("", 7)));
+        assert!(number::<u8>("-7").is_err());
+        assert!(number::<u8>("300").is_err());
+        assert_eq!(
+            parse_all("1,-2,3", numbers::<i32, _>(char(','))),
+            [1, -2, 3]
+        );
+        assert_eq!(parse_all("79 14\t55", spaced_numbers::<u64>), [79, 14, 55]);
+    }
+
+    #[test]
+    fn test_structure() {
+        let robot = pair(
+            preceded(tag("p="), coordinate(char(','))),
+            preceded(tag(" v="), coordinate(char(','))),
+        );
+        assert_eq!(
+            parse_all("p=0,4 v=3,-3\np=6,3 v=-1,-3\n", lines(robot)),
+            [
+                (Coordinate::new(0, 4), Coordinate::new(3, -3)),
+                (Coordinate::new(6, 3), Coordinate::new(-1, -3)),
+            ]
+        );
+        assert_eq!(
+            parse_all("a\nb\n\nc", blocks(lines(alpha1))),
+            [vec!["a", "b"], vec!["c"]]
+        );
+        assert_eq!(
+            parse_all("Register A: 729", field("Register A", number::<u32>)),
+            729
+        );
+    }
+
+    #[test]
+    fn test_grid() {
+        let (rest, grid) = grid(|c| c == '#')("#.\n.#\n\n12").unwrap();
+        assert_eq!(rest, "\n\n12");
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert!(grid[Coordinate::new(1, 1)] && !grid[Coordinate::new(1, 0)]);
+        assert!(super::grid(|c| c)("##\n#").is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "in coordinate")]
+    fn test_error_context() {
+        parse_all("1,2\n3;4\n5,6", lines(coordinate(char(','))));
+    }
+}
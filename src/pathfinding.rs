@@ -0,0 +1,383 @@
+//! Shortest path searches over any state, driven by closures yielding the neighbours of a state.
+//!
+//! All searches start from any number of states and stop once every goal at the best cost has
+//! been found, or when they run out of states if there is no goal. Besides the costs they record
+//! every optimal predecessor, so both a single path and all optimal paths can be recovered. To
+//! search backwards, e.g. from the end towards many possible starts, pass a closure returning the
+//! states leading to a state instead.
+
+use ahash::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The outcome of a search.
+#[derive(Clone, Debug)]
+pub struct Search<N, C> {
+    /// The cheapest cost to reach every state seen.
+    pub costs: HashMap<N, C>,
+    /// All goals reached at the lowest cost, in the order they were found.
+    pub goals: Vec<N>,
+    predecessors: HashMap<N, Vec<N>>,
+}
+
+impl<N, C> Default for Search<N, C> {
+    fn default() -> Self {
+        Self {
+            costs: HashMap::default(),
+            goals: Vec::new(),
+            predecessors: HashMap::default(),
+        }
+    }
+}
+
+impl<N: Copy + Eq + Hash, C: Copy> Search<N, C> {
+    /// Returns the cheapest cost to reach `node`, if it was reached.
+    pub fn cost(&self, node: N) -> Option<C> {
+        self.costs.get(&node).copied()
+    }
+
+    /// Returns the cost to reach the goals, if any was reached.
+    pub fn goal_cost(&self) -> Option<C> {
+        self.cost(*self.goals.first()?)
+    }
+
+    /// Returns all states directly preceding `node` on an optimal path.
+    pub fn predecessors(&self, node: N) -> &[N] {
+        self.predecessors.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns one optimal path from a start to `to`, including both.
+    pub fn path(&self, to: N) -> Option<Vec<N>> {
+        self.costs.contains_key(&to).then(|| {
+            let mut path = vec![to];
+            while let Some(&previous) = self.predecessors(*path.last().unwrap()).first() {
+                path.push(previous);
+            }
+            path.reverse();
+            path
+        })
+    }
+
+    /// Returns all states on any optimal path from a start to any of `to`.
+    pub fn optimal_nodes(&self, to: impl IntoIterator<Item = N>) -> HashSet<N> {
+        let mut nodes = HashSet::default();
+        let mut queue: Vec<N> = to
+            .into_iter()
+            .filter(|n| self.costs.contains_key(n))
+            .collect();
+        while let Some(node) = queue.pop() {
+            if nodes.insert(node) {
+                queue.extend_from_slice(self.predecessors(node));
+            }
+        }
+        nodes
+    }
+
+    /// Returns `true` if `ancestor` precedes `node` on an optimal path through zero-cost steps, or
+    /// is `node` itself.
+    fn is_ancestor(&self, ancestor: N, node: N) -> bool
+    where
+        C: Eq,
+    {
+        let cost = self.costs[&node];
+        let mut seen = HashSet::default();
+        let mut queue = vec![node];
+        while let Some(node) = queue.pop() {
+            if node == ancestor {
+                return true;
+            }
+            if seen.insert(node) {
+                let free = self
+                    .predecessors(node)
+                    .iter()
+                    .filter(|p| self.costs[*p] == cost);
+                queue.extend(free);
+            }
+        }
+        false
+    }
+
+    /// Records reaching `next` from `node` at `cost`, returning `true` if this is a new best.
+    /// Equally cheap ways are kept as further predecessors, unless a zero-cost step would close a
+    /// cycle, which would make the predecessors loop.
+    fn relax(&mut self, node: N, next: N, cost: C) -> bool
+    where
+        C: Ord,
+    {
+        match self.costs.entry(next) {
+            Entry::Vacant(entry) => {
+                entry.insert(cost);
+            }
+            Entry::Occupied(mut entry) => match cost.cmp(entry.get()) {
+                Ordering::Less => {
+                    entry.insert(cost);
+                }
+                Ordering::Equal => {
+                    // Only a step costing nothing can lead back to where it came from.
+                    if self.costs[&node] < cost || !self.is_ancestor(next, node) {
+                        self.predecessors.entry(next).or_default().push(node);
+                    }
+                    return false;
+                }
+                Ordering::Greater => return false,
+            },
+        }
+        self.predecessors.insert(next, vec![node]);
+        true
+    }
+}
+
+/// Breadth-first search where every step costs one.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(N) -> I,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Search<N, usize>
+where
+    N: Copy + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::default();
+    let mut queue = VecDeque::new();
+    let mut best = None;
+    for start in starts {
+        search.costs.insert(start, 0);
+        queue.push_back((0, start));
+    }
+    while let Some((cost, node)) = queue.pop_front() {
+        if best.is_some_and(|best| cost > best) {
+            break;
+        }
+        if is_goal(node) {
+            best = Some(cost);
+            search.goals.push(node);
+            continue;
+        }
+        for next in neighbours(node) {
+            if search.relax(node, next, cost + 1) {
+                queue.push_back((cost + 1, next));
+            }
+        }
+    }
+    search
+}
+
+/// Dijkstra's algorithm, costs must not be negative.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbours: impl FnMut(N) -> I,
+    is_goal: impl FnMut(N) -> bool,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search, guided by a `heuristic` which must never overestimate the remaining cost, and not
+/// drop by more than the cost of a step.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(N) -> I,
+    mut heuristic: impl FnMut(N) -> C,
+    mut is_goal: impl FnMut(N) -> bool,
+) -> Search<N, C>
+where
+    N: Copy + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search::default();
+    let mut queue = BinaryHeap::new();
+    let mut best = None;
+    for start in starts {
+        search.costs.insert(start, C::default());
+        queue.push(Candidate {
+            estimate: heuristic(start),
+            cost: C::default(),
+            node: start,
+        });
+    }
+    while let Some(Candidate {
+        estimate,
+        cost,
+        node,
+    }) = queue.pop()
+    {
+        if best.is_some_and(|best| estimate > best) {
+            break;
+        }
+        if search.costs[&node] < cost {
+            // Found a better way here after queueing this one.
+            continue;
+        }
+        if is_goal(node) {
+            best = Some(cost);
+            search.goals.push(node);
+            continue;
+        }
+        for (next, step) in neighbours(node) {
+            let cost = cost + step;
+            if search.relax(node, next, cost) {
+                queue.push(Candidate {
+                    estimate: cost + heuristic(next),
+                    cost,
+                    node: next,
+                });
+            }
+        }
+    }
+    search
+}
+
+/// A queued state, ordered so the lowest estimate pops first from a max-heap.
+struct Candidate<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Candidate<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for Candidate<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Candidate<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Candidate<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coordinate::Coordinate;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+S..#
+.#..
+...E";
+
+    fn maze() -> (Grid<char>, Coordinate, Coordinate) {
+        let grid = Grid::parse(MAZE, |c| c);
+        let start = grid.find(|&c| c == 'S').unwrap();
+        let end = grid.find(|&c| c == 'E').unwrap();
+        (grid, start, end)
+    }
+
+    #[test]
+    fn test_bfs() {
+        let (grid, start, end) = maze();
+        let open = |c: Coordinate| grid.neighbours(c).filter(|&n| grid[n] != '#');
+        let search = bfs([start], open, |c| c == end);
+        assert_eq!(search.goal_cost(), Some(5));
+        let path = search.path(end).unwrap();
+        assert_eq!((path[0], path[5]), (start, end));
+        // Three equally short ways around the pillar, covering every open cell.
+        assert_eq!(search.optimal_nodes([end]).len(), 10);
+
+        let everything = bfs([start], open, |_| false);
+        assert_eq!(everything.costs.len(), 10);
+        assert_eq!(everything.cost(Coordinate::new(3, 1)), Some(4));
+    }
+
+    #[test]
+    fn test_multi_source() {
+        let (grid, _, end) = maze();
+        let open = |c: Coordinate| grid.neighbours(c).filter(|&n| grid[n] != '#');
+        let starts = [Coordinate::new(0, 2), Coordinate::new(3, 1)];
+        let search = bfs(starts, open, |c| c == end);
+        assert_eq!(search.goal_cost(), Some(1));
+        assert_eq!(search.path(end), Some(vec![starts[1], end]));
+        assert_eq!(search.cost(Coordinate::new(1, 2)), Some(1));
+    }
+
+    #[test]
+    fn test_weighted() {
+        // Walking sideways is cheap, walking up or down is expensive.
+        let (grid, start, end) = maze();
+        let weighted = |c: Coordinate| {
+            grid.neighbours(c)
+                .filter(|&n| grid[n] != '#')
+                .map(move |n| (n, if n.y == c.y { 1 } else { 10 }))
+                .collect::<Vec<_>>()
+        };
+        let search = dijkstra([start], weighted, |c| c == end);
+        assert_eq!(search.goal_cost(), Some(23));
+        assert_eq!(search.optimal_nodes(search.goals.clone()).len(), 10);
+
+        let guided = astar(
+            [start],
+            weighted,
+            |c| c.manhattan_distance(end),
+            |c| c == end,
+        );
+        assert_eq!(guided.goal_cost(), Some(23));
+        assert_eq!(guided.path(end).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn test_unreachable() {
+        let search = bfs(
+            [0],
+            |n: u8| [n + 1].into_iter().filter(|&n| n < 5),
+            |n| n == 7,
+        );
+        assert_eq!(search.goal_cost(), None);
+        assert_eq!(search.path(4), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(search.path(7), None);
+    }
+
+    #[test]
+    fn test_zero_cost_cycle() {
+        // 0 and 1 lead to each other for free, and so do 2 and 3.
+        let edges = |n: u8| match n {
+            0 => vec![(1, 0)],
+            1 => vec![(0, 0), (2, 1)],
+            2 => vec![(3, 0)],
+            3 => vec![(2, 0), (4, 1)],
+            _ => vec![],
+        };
+        let search = dijkstra([0], edges, |n| n == 4);
+        assert_eq!(search.goal_cost(), Some(2));
+        assert_eq!(search.path(4), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(search.predecessors(0), []);
+        assert_eq!(search.predecessors(2), [1]);
+        assert_eq!(search.optimal_nodes([4]).len(), 5);
+    }
+
+    #[test]
+    fn test_zero_cost_dag() {
+        // X can be reached for free both directly and through A.
+        let edges = |n: char| match n {
+            'S' => vec![('A', 0), ('X', 0)],
+            'A' => vec![('X', 0)],
+            _ => vec![],
+        };
+        let search = dijkstra(['S'], edges, |_| false);
+        assert_eq!(search.cost('X'), Some(0));
+        let mut predecessors = search.predecessors('X').to_vec();
+        predecessors.sort_unstable();
+        assert_eq!(predecessors, ['A', 'S']);
+        assert_eq!(
+            search.optimal_nodes(['X']),
+            HashSet::from_iter("SAX".chars())
+        );
+        assert!(search.path('X').is_some());
+    }
+}
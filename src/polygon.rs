@@ -0,0 +1,187 @@
+//! Polygons with corners on the integer grid, for areas enclosed by loops and the outlines of
+//! regions of cells.
+
+use crate::coordinate::{Coordinate, Direction};
+use crate::math::gcd;
+use rustc_hash::FxHashSet;
+
+/// A closed polygon, with the last vertex connected back to the first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Coordinate>,
+}
+
+impl Polygon {
+    /// Creates a polygon from a closed path of points, dropping those in the middle of a straight
+    /// line, so only the corners remain.
+    pub fn new(points: impl IntoIterator<Item = Coordinate>) -> Self {
+        let mut points: Vec<Coordinate> = points.into_iter().collect();
+        points.dedup();
+        if points.len() > 1 && points.first() == points.last() {
+            points.pop();
+        }
+        let n = points.len();
+        let vertices = (0..n)
+            .filter(|&i| {
+                let (previous, point, next) =
+                    (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+                let (a, b) = (point - previous, next - point);
+                // Keep turns, and reversals which are corners of zero width.
+                a.x * b.y != a.y * b.x || a.x * b.x + a.y * b.y < 0
+            })
+            .map(|i| points[i])
+            .collect();
+        Self { vertices }
+    }
+
+    /// Returns the corners in order.
+    pub fn vertices(&self) -> &[Coordinate] {
+        &self.vertices
+    }
+
+    pub fn corners(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns the number of straight sides, which for a closed polygon is the number of corners.
+    pub fn sides(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Returns pairs of consecutive vertices, including the last one back to the first.
+    fn edges(&self) -> impl Iterator<Item = (Coordinate, Coordinate)> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Returns twice the area by the shoelace formula, positive if the vertices go clockwise with
+    /// the origin in the top left, and negative if they go counter-clockwise.
+    pub fn signed_double_area(&self) -> isize {
+        self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum()
+    }
+
+    /// Returns twice the area, which is always a whole number for corners on the grid.
+    pub fn double_area(&self) -> usize {
+        self.signed_double_area().unsigned_abs()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.double_area() as f64 / 2.0
+    }
+
+    /// Returns the number of grid points on the edges.
+    pub fn boundary_points(&self) -> usize {
+        self.edges()
+            .map(|(a, b)| gcd(a.x.abs_diff(b.x) as u64, a.y.abs_diff(b.y) as u64) as usize)
+            .sum()
+    }
+
+    /// Returns the number of grid points strictly inside, by Pick's theorem. Degenerate polygons
+    /// without any area have none.
+    pub fn interior_points(&self) -> usize {
+        match self.double_area() {
+            0 => 0,
+            double_area => (double_area + 2).saturating_sub(self.boundary_points()) / 2,
+        }
+    }
+}
+
+/// Returns the outlines of a region of cells, with each cell a unit square whose top left corner
+/// is its coordinate. The outer outlines go clockwise and those of holes counter-clockwise, so
+/// the signed areas add up to the number of cells. Cells touching only at a corner get separate
+/// outlines.
+pub fn trace_region(cells: impl IntoIterator<Item = Coordinate>) -> Vec<Polygon> {
+    let cells: FxHashSet<Coordinate> = cells.into_iter().collect();
+    // The sides of cells facing outside, as a corner and a heading, with the cell on the right.
+    let edges: FxHashSet<(Coordinate, Direction)> = cells
+        .iter()
+        .flat_map(|&c| {
+            let cells = &cells;
+            [
+                (Direction::Up, c, Direction::Right),
+                (Direction::Right, c + Coordinate::new(1, 0), Direction::Down),
+                (Direction::Down, c + Coordinate::new(1, 1), Direction::Left),
+                (Direction::Left, c + Coordinate::new(0, 1), Direction::Up),
+            ]
+            .into_iter()
+            .filter(move |(side, _, _)| !cells.contains(&(c + side.offset())))
+            .map(|(_, corner, heading)| (corner, heading))
+        })
+        .collect();
+
+    let mut unvisited = edges.clone();
+    let mut outlines = vec![];
+    while let Some(&first) = unvisited.iter().next() {
+        let mut points = vec![];
+        let mut edge = first;
+        loop {
+            unvisited.remove(&edge);
+            points.push(edge.0);
+            let (corner, heading) = edge;
+            let next = corner + heading.offset();
+            // Turning right first keeps to the cells on this side of a corner where two touch.
+            edge = [heading.turn_right(), heading, heading.turn_left()]
+                .into_iter()
+                .map(|heading| (next, heading))
+                .find(|edge| edges.contains(edge))
+                .expect("outline is closed");
+            if edge == first {
+                break;
+            }
+        }
+        outlines.push(Polygon::new(points));
+    }
+    outlines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    fn polygon(points: &[(isize, isize)]) -> Polygon {
+        Polygon::new(points.iter().map(|&p| Coordinate::from(p)))
+    }
+
+    #[test]
+    fn test_polygon() {
+        let square = polygon(&[(0, 0), (2, 0), (4, 0), (4, 4), (0, 4), (0, 2), (0, 0)]);
+        assert_eq!(square, polygon(&[(0, 0), (4, 0), (4, 4), (0, 4)]));
+        assert_eq!((square.sides(), square.corners()), (4, 4));
+        assert_eq!(square.signed_double_area(), 32);
+        assert_eq!(square.area(), 16.0);
+        assert_eq!(square.boundary_points(), 16);
+        assert_eq!(square.interior_points(), 9);
+
+        let triangle = polygon(&[(0, 0), (0, 3), (6, 0)]);
+        assert_eq!(triangle.signed_double_area(), -18);
+        assert_eq!(triangle.boundary_points(), 3 + 3 + 6);
+        assert_eq!(triangle.interior_points(), 4);
+    }
+
+    #[test]
+    fn test_degenerate_polygon() {
+        // There and back along a line, with no area at all.
+        let line = polygon(&[(0, 0), (3, 0), (0, 0)]);
+        assert_eq!(line.double_area(), 0);
+        assert_eq!(line.boundary_points(), 6);
+        assert_eq!(line.interior_points(), 0);
+        assert_eq!(polygon(&[(2, 2)]).interior_points(), 0);
+        assert_eq!(polygon(&[]).interior_points(), 0);
+    }
+
+    #[test]
+    fn test_trace_region() {
+        // A ring with a hole, and a separate cell touching it at a corner.
+        let cells = Grid::parse("###..\n#.#..\n####.\n....#", |c| c == '#');
+        let mut outlines = trace_region(cells.positions(|&c| c));
+        outlines.sort_by_key(|o| o.signed_double_area());
+        let areas: Vec<isize> = outlines.iter().map(|o| o.signed_double_area()).collect();
+        assert_eq!(areas, [-2, 2, 20]);
+        let sides: Vec<usize> = outlines.iter().map(Polygon::sides).collect();
+        assert_eq!(sides, [4, 4, 6]);
+        assert_eq!(trace_region([]), []);
+    }
+}
@@ -0,0 +1,264 @@
+//! Watching simulations step by step in the terminal, for `aoc replay YEAR DAY`.
+//!
+//! Commands are read a line at a time, so they work in any terminal without raw mode:
+//!
+//! - Enter or `p` pauses and resumes.
+//! - `s` takes a single step and pauses.
+//! - `+` and `-` halve and double the time per step.
+//! - `g N`, or just `N`, goes to step `N`, starting over if it's in the past.
+//! - `q` quits.
+
+use crate::viz::Image;
+use std::{
+    io::{self, Write},
+    str::FromStr,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// A puzzle whose state can be watched as it changes.
+pub trait Simulation {
+    /// Advances by one step. Returns `false`, without changing anything, once it has finished.
+    fn step(&mut self) -> bool;
+
+    /// Draws the current state, a pixel per cell.
+    fn image(&self) -> Image;
+}
+
+/// Creates a simulation of a puzzle in its initial state, usually with the bundled input.
+pub type Replay = fn() -> Box<dyn Simulation>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Command {
+    TogglePause,
+    Step,
+    Faster,
+    Slower,
+    Jump(usize),
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "" | "p" => Ok(Self::TogglePause),
+            "s" => Ok(Self::Step),
+            "+" => Ok(Self::Faster),
+            "-" => Ok(Self::Slower),
+            "q" => Ok(Self::Quit),
+            s => s
+                .strip_prefix('g')
+                .unwrap_or(s)
+                .trim()
+                .parse()
+                .map(Self::Jump)
+                .map_err(|_| "unknown command"),
+        }
+    }
+}
+
+/// The state of a replay apart from input and output.
+struct Player {
+    replay: Replay,
+    simulation: Box<dyn Simulation>,
+    /// The number of steps taken so far.
+    step: usize,
+    finished: bool,
+    paused: bool,
+    delay: Duration,
+}
+
+impl Player {
+    fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            simulation: replay(),
+            step: 0,
+            finished: false,
+            paused: true,
+            delay: Duration::from_millis(100),
+        }
+    }
+
+    fn advance(&mut self) {
+        if !self.finished && self.simulation.step() {
+            self.step += 1;
+        } else {
+            self.finished = true;
+        }
+    }
+
+    /// Goes to step `target`, or as close as the simulation gets.
+    fn jump(&mut self, target: usize) {
+        if target < self.step {
+            self.simulation = (self.replay)();
+            self.step = 0;
+            self.finished = false;
+        }
+        while self.step < target && !self.finished {
+            self.advance();
+        }
+    }
+
+    fn apply(&mut self, command: Command) {
+        match command {
+            Command::TogglePause => self.paused = !self.paused,
+            Command::Step => {
+                self.paused = true;
+                self.advance();
+            }
+            Command::Faster => self.delay = (self.delay / 2).max(MIN_DELAY),
+            Command::Slower => self.delay = (self.delay * 2).min(MAX_DELAY),
+            Command::Jump(target) => self.jump(target),
+            Command::Quit => {}
+        }
+    }
+
+    /// Whether to wait for a command rather than keep stepping.
+    fn is_waiting(&self) -> bool {
+        self.paused || self.finished
+    }
+
+    fn draw(&self, out: &mut impl Write, message: Option<&str>) -> io::Result<()> {
+        let state = match (self.finished, self.paused) {
+            (true, _) => "finished",
+            (false, true) => "paused",
+            (false, false) => "playing",
+        };
+        // Move to the top left and clear the screen.
+        write!(out, "\x1b[H\x1b[2J{}", self.simulation.image().terminal())?;
+        writeln!(
+            out,
+            "step {} · {state} · {} ms per step",
+            self.step,
+            self.delay.as_millis()
+        )?;
+        writeln!(
+            out,
+            "{}",
+            message
+                .unwrap_or("Enter: play/pause · s: step · +/-: speed · g N: go to step · q: quit")
+        )?;
+        out.flush()
+    }
+}
+
+/// Replays a simulation in the terminal until the user quits, or until it has finished and stdin
+/// is closed.
+pub fn play(replay: Replay) -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lines() {
+            if line.map(|line| sender.send(line)).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut player = Player::new(replay);
+    let mut message = None;
+    let mut out = io::stdout().lock();
+    loop {
+        player.draw(&mut out, message.take())?;
+        let line = if player.is_waiting() {
+            match receiver.recv() {
+                Ok(line) => line,
+                Err(_) if player.finished => return Ok(()),
+                Err(_) => {
+                    player.paused = false;
+                    continue;
+                }
+            }
+        } else {
+            match receiver.recv_timeout(player.delay) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    player.advance();
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    // Nobody is watching the keyboard, just play on.
+                    thread::sleep(player.delay);
+                    player.advance();
+                    continue;
+                }
+            }
+        };
+        match line.parse() {
+            Ok(Command::Quit) => return Ok(()),
+            Ok(command) => player.apply(command),
+            Err(e) => message = Some(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::viz::Rgb;
+
+    /// Counts up to three.
+    struct Counter(usize);
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> bool {
+            self.0 < 3 && {
+                self.0 += 1;
+                true
+            }
+        }
+
+        fn image(&self) -> Image {
+            Image::new(self.0, 1, Rgb::WHITE)
+        }
+    }
+
+    fn counter() -> Box<dyn Simulation> {
+        Box::new(Counter(0))
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!("".parse(), Ok(Command::TogglePause));
+        assert_eq!(" s\n".parse(), Ok(Command::Step));
+        assert_eq!("g 12".parse(), Ok(Command::Jump(12)));
+        assert_eq!("7".parse(), Ok(Command::Jump(7)));
+        assert_eq!("x".parse::<Command>(), Err("unknown command"));
+    }
+
+    #[test]
+    fn test_player() {
+        let mut player = Player::new(counter);
+        assert!(player.is_waiting());
+        player.apply(Command::Step);
+        player.apply(Command::Step);
+        assert_eq!(player.step, 2);
+        player.apply(Command::Jump(10));
+        assert_eq!(player.step, 3);
+        assert!(player.finished);
+        player.apply(Command::Jump(1));
+        assert_eq!((player.step, player.finished), (1, false));
+        assert_eq!(player.simulation.image().width(), 1);
+
+        player.apply(Command::Faster);
+        assert_eq!(player.delay, Duration::from_millis(50));
+        (0..20).for_each(|_| player.apply(Command::Slower));
+        assert_eq!(player.delay, MAX_DELAY);
+    }
+
+    #[test]
+    fn test_draw() {
+        let player = Player::new(counter);
+        let mut out = Vec::new();
+        player.draw(&mut out, Some("unknown command")).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("\x1b[H\x1b[2J"));
+        assert!(out.ends_with("step 0 · paused · 100 ms per step\nunknown command\n"));
+    }
+}
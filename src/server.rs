@@ -0,0 +1,556 @@
+//! A small HTTP/JSON server exposing the solvers, so other tools can solve inputs without shelling
+//! out to the binary.
+//!
+//! - `GET /solvers` lists all available solvers.
+//! - `POST /solve` takes `{"year": 2024, "day": 1, "input": "..."}` and returns both answers and
+//!   the time taken, or an error.
+//!
+//! Connections and running solvers are limited, requests beyond that get a 503. Each solver runs
+//! in a child process, `aoc solve YEAR DAY`, which is killed if it takes too long.
+
+use crate::DAYS;
+use simd_json::prelude::*;
+use simd_json::{json, OwnedValue};
+use std::any::Any;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::{env, panic, thread};
+
+/// Largest request body accepted, puzzle inputs are far below this.
+const MAX_BODY_SIZE: usize = 4 * 1024 * 1024;
+
+/// Longest request or header line accepted, including the line ending.
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+
+/// Most connections handled at once.
+const MAX_CONNECTIONS: usize = 64;
+
+/// How long a client gets to send its request or read the response.
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Listens on localhost, handling each connection on its own thread. Solvers get at most
+/// `timeout` before the request fails, and only as many run at once as there are cores.
+pub fn serve(port: u16, timeout: Duration) -> io::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    println!("Listening on http://{}", listener.local_addr()?);
+    let connections = Limit::new(MAX_CONNECTIONS);
+    let server = Arc::new(Server {
+        timeout,
+        solves: Limit::new(thread::available_parallelism().map_or(4, usize::from)),
+        solver: solver_process,
+    });
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                eprintln!("accepting connection failed: {e}");
+                continue;
+            }
+        };
+        let Some(permit) = connections.try_acquire() else {
+            let busy = Response::error(503, "too many connections");
+            if let Err(e) = stream
+                .set_write_timeout(Some(IO_TIMEOUT))
+                .and_then(|_| write_response(&stream, busy))
+            {
+                eprintln!("connection failed: {e}");
+            }
+            continue;
+        };
+        let server = Arc::clone(&server);
+        thread::spawn(move || {
+            if let Err(e) = server.handle_connection(stream) {
+                eprintln!("connection failed: {e}");
+            }
+            drop(permit);
+        });
+    }
+    Ok(())
+}
+
+/// A counting semaphore which turns work away rather than waiting once it's used up.
+#[derive(Debug)]
+struct Limit {
+    in_use: AtomicUsize,
+    max: usize,
+}
+
+impl Limit {
+    fn new(max: usize) -> Arc<Self> {
+        Arc::new(Self {
+            in_use: AtomicUsize::new(0),
+            max,
+        })
+    }
+
+    fn try_acquire(self: &Arc<Self>) -> Option<Permit> {
+        self.in_use
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Permit(Arc::clone(self)))
+    }
+}
+
+/// One unit of a [`Limit`], given back when dropped.
+#[derive(Debug)]
+struct Permit(Arc<Limit>);
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        self.0.in_use.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// What all connections share.
+struct Server {
+    timeout: Duration,
+    solves: Arc<Limit>,
+    /// Creates the command solving a year and day, see [`solve_stdin`].
+    solver: fn(u32, u32) -> io::Result<Command>,
+}
+
+/// Runs [`solve_stdin`] in a copy of the current executable.
+fn solver_process(year: u32, day: u32) -> io::Result<Command> {
+    let mut command = Command::new(env::current_exe()?);
+    command.args(["solve", &year.to_string(), &day.to_string()]);
+    Ok(command)
+}
+
+struct Response {
+    status: u16,
+    body: OwnedValue,
+}
+
+impl Response {
+    fn ok(body: OwnedValue) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            413 => "Payload Too Large",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+impl Server {
+    fn handle_connection(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(IO_TIMEOUT))?;
+        stream.set_write_timeout(Some(IO_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+
+        let Some(request_line) = read_line(&mut reader)? else {
+            return write_response(&stream, Response::error(400, "request line too long"));
+        };
+        let mut parts = request_line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return write_response(&stream, Response::error(400, "malformed request line"));
+        };
+        let path = target.split('?').next().unwrap_or(target);
+
+        let mut content_length = 0;
+        loop {
+            let Some(header) = read_line(&mut reader)? else {
+                return write_response(&stream, Response::error(431, "header line too long"));
+            };
+            if header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    let Ok(length) = value.trim().parse() else {
+                        return write_response(
+                            &stream,
+                            Response::error(400, "invalid content length"),
+                        );
+                    };
+                    content_length = length;
+                }
+            }
+        }
+        if content_length > MAX_BODY_SIZE {
+            return write_response(&stream, Response::error(413, "request body too large"));
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+        write_response(&stream, self.route(method, path, body))
+    }
+
+    fn route(&self, method: &str, path: &str, body: Vec<u8>) -> Response {
+        match (method, path) {
+            ("GET", "/solvers") => list_solvers(),
+            ("POST", "/solve") => self.solve(body),
+            (_, "/solvers" | "/solve") => Response::error(405, "method not allowed"),
+            _ => Response::error(404, "not found"),
+        }
+    }
+
+    fn solve(&self, mut body: Vec<u8>) -> Response {
+        let request = match simd_json::to_borrowed_value(&mut body) {
+            Ok(request) => request,
+            Err(e) => return Response::error(400, format!("invalid JSON: {e}")),
+        };
+        let (Some(year), Some(day), Some(input)) = (
+            request.get_u32("year"),
+            request.get_u32("day"),
+            request.get_str("input"),
+        ) else {
+            return Response::error(400, "expected `year`, `day` and `input`");
+        };
+        if !DAYS.iter().any(|d| d.year == year && d.day == day) {
+            return Response::error(404, format!("no solver for {year}-{day:02}"));
+        }
+        // Held until the solver has finished or been killed.
+        let Some(_permit) = self.solves.try_acquire() else {
+            return Response::error(503, "too many solvers running");
+        };
+        let command = match (self.solver)(year, day) {
+            Ok(command) => command,
+            Err(e) => return Response::error(500, format!("can't start solver: {e}")),
+        };
+
+        match run_with_timeout(command, input, self.timeout) {
+            Ok(answers) => Response::ok(json!({
+                "year": year,
+                "day": day,
+                "part_1": answers.part_1,
+                "part_2": answers.part_2,
+                "time_ms": answers.time_ms,
+            })),
+            Err(RunError::Failed(message)) => {
+                Response::error(400, format!("solver failed: {message}"))
+            }
+            Err(RunError::TimedOut) => Response::error(
+                504,
+                format!("solver timed out after {} ms", self.timeout.as_millis()),
+            ),
+        }
+    }
+}
+
+/// Reads a line of at most [`MAX_LINE_LENGTH`] bytes, or `None` if it's longer. Returns an empty
+/// line at the end of the stream.
+fn read_line(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    reader
+        .by_ref()
+        .take(MAX_LINE_LENGTH as u64)
+        .read_line(&mut line)?;
+    Ok((line.len() < MAX_LINE_LENGTH || line.ends_with('\n')).then_some(line))
+}
+
+fn write_response(mut stream: &TcpStream, response: Response) -> io::Result<()> {
+    let body = response.body.encode();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        response.status,
+        response.reason(),
+        body.len(),
+    )?;
+    stream.flush()
+}
+
+fn list_solvers() -> Response {
+    let solvers: Vec<OwnedValue> = DAYS
+        .iter()
+        .map(|d| json!({ "year": d.year, "day": d.day }))
+        .collect();
+    Response::ok(json!({ "solvers": solvers }))
+}
+
+/// Solves the input on stdin and writes the answers to stdout as JSON, for `aoc solve`.
+pub fn solve_stdin(year: u32, day: u32) -> io::Result<()> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", answer(year, day, &input).encode())?;
+    stdout.flush()
+}
+
+/// Returns both answers and the time taken, or the error if the solver panics.
+fn answer(year: u32, day: u32, input: &str) -> OwnedValue {
+    let Some(solver) = DAYS.iter().find(|d| d.year == year && d.day == day) else {
+        return json!({ "error": format!("no solver for {year}-{day:02}") });
+    };
+    let start = Instant::now();
+    match panic::catch_unwind(|| (solver.run)(input)) {
+        Ok((part_1, part_2)) => json!({
+            "part_1": part_1,
+            "part_2": part_2,
+            "time_ms": start.elapsed().as_secs_f64() * 1000.0,
+        }),
+        Err(payload) => json!({ "error": panic_message(payload) }),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Answers {
+    part_1: String,
+    part_2: String,
+    time_ms: f64,
+}
+
+#[derive(Debug, PartialEq)]
+enum RunError {
+    Failed(String),
+    TimedOut,
+}
+
+/// Runs a solver process with `input` on its stdin, killing it after `timeout`.
+fn run_with_timeout(
+    mut command: Command,
+    input: &str,
+    timeout: Duration,
+) -> Result<Answers, RunError> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| RunError::Failed(format!("can't start solver: {e}")))?;
+    let (Some(mut stdin), Some(mut stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        unreachable!("solver pipes are set up above");
+    };
+    let input = input.to_string();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        // A solver failing early may not read all of its input, its output tells what happened.
+        let _ = stdin.write_all(input.as_bytes());
+        drop(stdin);
+        let mut output = Vec::new();
+        // The receiver is gone if we timed out, nobody is interested anymore.
+        let _ = tx.send(stdout.read_to_end(&mut output).map(|_| output));
+    });
+    let output = rx.recv_timeout(timeout);
+    if output.is_err() {
+        // Killing fails if it has just finished, which is just as good.
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+    match output {
+        Ok(Ok(output)) => parse_answers(output),
+        Ok(Err(e)) => Err(RunError::Failed(format!("can't read solver output: {e}"))),
+        Err(RecvTimeoutError::Timeout) => Err(RunError::TimedOut),
+        Err(RecvTimeoutError::Disconnected) => {
+            Err(RunError::Failed("solver output got lost".to_string()))
+        }
+    }
+}
+
+/// Parses the output of [`solve_stdin`].
+fn parse_answers(mut output: Vec<u8>) -> Result<Answers, RunError> {
+    let Ok(output) = simd_json::to_owned_value(&mut output) else {
+        return Err(RunError::Failed("solver process died".to_string()));
+    };
+    if let Some(error) = output.get_str("error") {
+        return Err(RunError::Failed(error.to_string()));
+    }
+    match (
+        output.get_str("part_1"),
+        output.get_str("part_2"),
+        output.get("time_ms").and_then(ValueAsScalar::cast_f64),
+    ) {
+        (Some(part_1), Some(part_2), Some(time_ms)) => Ok(Answers {
+            part_1: part_1.to_string(),
+            part_2: part_2.to_string(),
+            time_ms,
+        }),
+        _ => Err(RunError::Failed("solver process died".to_string())),
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// A shell command standing in for a solver process.
+    fn shell(script: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        command
+    }
+
+    /// Answers like 2015 day 1 does for `())`.
+    fn fake_solver(_: u32, _: u32) -> io::Result<Command> {
+        Ok(shell(
+            r#"cat > /dev/null; echo '{"part_1":"-1","part_2":"3","time_ms":0.1}'"#,
+        ))
+    }
+
+    fn endless_solver(_: u32, _: u32) -> io::Result<Command> {
+        let mut command = Command::new("sleep");
+        command.arg("60");
+        Ok(command)
+    }
+
+    fn server() -> Server {
+        Server {
+            timeout: TIMEOUT,
+            solves: Limit::new(2),
+            solver: fake_solver,
+        }
+    }
+
+    fn post_solve(body: &str) -> Response {
+        server().route("POST", "/solve", body.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_list_solvers() {
+        let response = server().route("GET", "/solvers", vec![]);
+        assert_eq!(response.status, 200);
+        let solvers = response.body.get_array("solvers").unwrap();
+        assert_eq!(solvers.len(), DAYS.len());
+        assert_eq!(solvers[0].get_u32("year"), Some(2015));
+        assert_eq!(solvers[0].get_u32("day"), Some(1));
+    }
+
+    #[test]
+    fn test_solve() {
+        let response = post_solve(r#"{"year": 2015, "day": 1, "input": "())"}"#);
+        assert_eq!(response.status, 200);
+        assert_eq!(response.body.get_str("part_1"), Some("-1"));
+        assert_eq!(response.body.get_str("part_2"), Some("3"));
+        assert!(response.body.get_f64("time_ms").is_some());
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        assert_eq!(post_solve("{").status, 400);
+        assert_eq!(post_solve(r#"{"year": 2015, "day": 1}"#).status, 400);
+        assert_eq!(
+            post_solve(r#"{"year": 2015, "day": 42, "input": ""}"#).status,
+            404
+        );
+        assert_eq!(server().route("GET", "/solve", vec![]).status, 405);
+        assert_eq!(server().route("GET", "/", vec![]).status, 404);
+    }
+
+    #[test]
+    fn test_busy() {
+        let server = server();
+        let permits = [server.solves.try_acquire(), server.solves.try_acquire()];
+        assert!(permits.iter().all(Option::is_some));
+        let body = r#"{"year": 2015, "day": 1, "input": "())"}"#.as_bytes().to_vec();
+        assert_eq!(server.route("POST", "/solve", body.clone()).status, 503);
+        drop(permits);
+        assert_eq!(server.route("POST", "/solve", body).status, 200);
+    }
+
+    #[test]
+    fn test_read_line() {
+        let mut reader = "GET / HTTP/1.1\r\nHost: x\r\n".as_bytes();
+        assert_eq!(
+            read_line(&mut reader).unwrap().as_deref(),
+            Some("GET / HTTP/1.1\r\n")
+        );
+        assert_eq!(
+            read_line(&mut reader).unwrap().as_deref(),
+            Some("Host: x\r\n")
+        );
+        assert_eq!(read_line(&mut reader).unwrap().as_deref(), Some(""));
+
+        let long = "x".repeat(MAX_LINE_LENGTH * 2);
+        assert_eq!(read_line(&mut long.as_bytes()).unwrap(), None);
+        let fits = format!("{}\n", "x".repeat(MAX_LINE_LENGTH - 1));
+        assert_eq!(read_line(&mut fits.as_bytes()).unwrap(), Some(fits));
+    }
+
+    #[test]
+    fn test_timed_out_solvers_are_killed() {
+        let server = Server {
+            timeout: Duration::from_millis(50),
+            solves: Limit::new(1),
+            solver: endless_solver,
+        };
+        let body = r#"{"year": 2015, "day": 1, "input": "())"}"#.as_bytes().to_vec();
+        let start = Instant::now();
+        // Without getting its permit back the second request would be turned away.
+        assert_eq!(server.route("POST", "/solve", body.clone()).status, 504);
+        assert_eq!(server.route("POST", "/solve", body).status, 504);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_answer() {
+        let answers = answer(2015, 1, "())");
+        assert_eq!(answers.get_str("part_1"), Some("-1"));
+        assert_eq!(answers.get_str("part_2"), Some("3"));
+        assert!(answers.get_f64("time_ms").is_some());
+        assert!(answer(2015, 1, "(").get_str("error").is_some());
+        assert_eq!(
+            answer(2015, 42, "").get_str("error"),
+            Some("no solver for 2015-42")
+        );
+    }
+
+    #[test]
+    fn test_run_with_timeout() {
+        let input = "x".repeat(1 << 20);
+        assert_eq!(
+            run_with_timeout(
+                shell(
+                    r#"wc -c | tr -d ' \n' | xargs printf '{"part_1":"%s","part_2":"","time_ms":1}'"#
+                ),
+                &input,
+                TIMEOUT
+            ),
+            Ok(Answers {
+                part_1: input.len().to_string(),
+                part_2: String::new(),
+                time_ms: 1.0,
+            })
+        );
+        assert_eq!(
+            run_with_timeout(shell(r#"echo '{"error":"bad input"}'"#), "", TIMEOUT),
+            Err(RunError::Failed("bad input".to_string()))
+        );
+        assert_eq!(
+            run_with_timeout(shell("kill -9 $$"), "", TIMEOUT),
+            Err(RunError::Failed("solver process died".to_string()))
+        );
+        // Never reads its input, which doesn't fit a pipe buffer.
+        assert_eq!(
+            run_with_timeout(
+                endless_solver(0, 0).unwrap(),
+                &input,
+                Duration::from_millis(50)
+            ),
+            Err(RunError::TimedOut)
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! An unbounded grid storing only its occupied cells, for things spreading out in any direction.
+
+use crate::coordinate::{Bounds, Coordinate};
+use rustc_hash::FxHashMap as HashMap;
+use std::cell::Cell;
+
+/// A map from coordinates to values which keeps track of the bounds of its cells.
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Coordinate, T>,
+    /// The bounds of all cells, or `None` after a removal which may have shrunk them.
+    bounds: Cell<Option<Bounds>>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::default(),
+            bounds: Cell::new(Some(Bounds::default())),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a string using rows and columns as coordinates, with the origin at the top left,
+    /// keeping the cells for which `f` returns `Some(T)`.
+    pub fn parse(s: &str, mut f: impl FnMut(char) -> Option<T>) -> Self {
+        let mut grid = Self::new();
+        for (y, line) in s.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                if let Some(value) = f(c) {
+                    grid.insert(Coordinate::new(x as isize, y as isize), value);
+                }
+            }
+        }
+        grid
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, c: Coordinate) -> bool {
+        self.cells.contains_key(&c)
+    }
+
+    pub fn get(&self, c: Coordinate) -> Option<&T> {
+        self.cells.get(&c)
+    }
+
+    pub fn get_mut(&mut self, c: Coordinate) -> Option<&mut T> {
+        self.cells.get_mut(&c)
+    }
+
+    /// Sets the value at `c`, returning the previous one.
+    pub fn insert(&mut self, c: Coordinate, value: T) -> Option<T> {
+        if self.cells.is_empty() {
+            self.bounds.set(Some(Bounds::spanning(c, c)));
+        } else if let Some(bounds) = self.bounds.get() {
+            self.bounds.set(Some(bounds.including(c)));
+        }
+        self.cells.insert(c, value)
+    }
+
+    /// Removes the value at `c`, returning it.
+    pub fn remove(&mut self, c: Coordinate) -> Option<T> {
+        let value = self.cells.remove(&c)?;
+        let on_edge = self.bounds.get().is_some_and(|b| {
+            c.x == b.min.x || c.y == b.min.y || c.x == b.max.x - 1 || c.y == b.max.y - 1
+        });
+        if on_edge {
+            self.bounds.set(None);
+        }
+        Some(value)
+    }
+
+    /// Returns the smallest bounds containing every cell, empty if there are none.
+    pub fn bounds(&self) -> Bounds {
+        self.bounds.get().unwrap_or_else(|| {
+            let bounds = Bounds::enclosing(self.cells.keys().copied()).unwrap_or_default();
+            self.bounds.set(Some(bounds));
+            bounds
+        })
+    }
+
+    /// Returns the number of cells within the bounds without a value.
+    pub fn empty_cells(&self) -> usize {
+        let bounds = self.bounds();
+        bounds.width() * bounds.height() - self.len()
+    }
+
+    /// Returns all cells with their values, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinate, &T)> {
+        self.cells.iter().map(|(&c, value)| (c, value))
+    }
+
+    /// Returns the coordinates of all cells, in no particular order.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Renders the area within the bounds as one line per row, turning every cell, whether it has
+    /// a value or not, into a character with `f`.
+    pub fn render(&self, mut f: impl FnMut(Option<&T>) -> char) -> String {
+        let bounds = self.bounds();
+        let mut text = String::with_capacity((bounds.width() + 1) * bounds.height());
+        for y in bounds.min.y..bounds.max.y {
+            if y > bounds.min.y {
+                text.push('\n');
+            }
+            text.extend((bounds.min.x..bounds.max.x).map(|x| f(self.get(Coordinate::new(x, y)))));
+        }
+        text
+    }
+}
+
+impl<T> FromIterator<(Coordinate, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Coordinate, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        iter.into_iter().for_each(|(c, value)| {
+            grid.insert(c, value);
+        });
+        grid
+    }
+}
+
+impl FromIterator<Coordinate> for SparseGrid<()> {
+    fn from_iter<I: IntoIterator<Item = Coordinate>>(iter: I) -> Self {
+        iter.into_iter().map(|c| (c, ())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = SparseGrid::parse("..#\n#..", |c| (c == '#').then_some(c));
+        assert_eq!(grid.bounds(), Bounds::new(0..3, 0..2));
+        assert_eq!(grid.empty_cells(), 4);
+        grid.insert(Coordinate::new(-2, 4), '#');
+        assert_eq!(grid.bounds(), Bounds::new(-2..3, 0..5));
+        assert_eq!(grid.remove(Coordinate::new(-2, 4)), Some('#'));
+        assert_eq!(grid.bounds(), Bounds::new(0..3, 0..2));
+        grid.remove(Coordinate::new(2, 0));
+        grid.remove(Coordinate::new(0, 1));
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), Bounds::default());
+        assert_eq!(grid.empty_cells(), 0);
+    }
+
+    #[test]
+    fn test_render() {
+        let trail: SparseGrid<()> = [(0, 0), (1, -1), (3, 0)]
+            .map(Coordinate::from)
+            .into_iter()
+            .collect();
+        assert_eq!(
+            trail.render(|c| if c.is_some() { '#' } else { '.' }),
+            ".#..\n#..#"
+        );
+        assert_eq!(SparseGrid::<()>::new().render(|_| '#'), "");
+    }
+}
@@ -0,0 +1,225 @@
+//! Disjoint sets for grouping things which are connected, all at once or as connections appear.
+
+use crate::coordinate::Coordinate;
+use crate::grid::Grid;
+use ahash::HashMap;
+use std::hash::Hash;
+
+/// Disjoint sets of the indices `0..len`, with union by rank and path compression.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+    sizes: Vec<usize>,
+    sets: usize,
+}
+
+impl UnionFind {
+    /// Creates `len` sets of one index each.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            ranks: vec![0; len],
+            sizes: vec![1; len],
+            sets: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Returns the number of disjoint sets.
+    pub fn sets(&self) -> usize {
+        self.sets
+    }
+
+    /// Returns the representative of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        // Point everything on the way straight at the root.
+        let mut x = x;
+        while self.parents[x] != root {
+            let parent = self.parents[x];
+            self.parents[x] = root;
+            x = parent;
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`. Returns `false` if they were already the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.ranks[a] < self.ranks[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        if self.ranks[a] == self.ranks[b] {
+            self.ranks[a] += 1;
+        }
+        self.sets -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns the size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.sizes[root]
+    }
+
+    /// Returns a label in `0..sets()` for every index, numbering sets in order of their smallest
+    /// index.
+    pub fn labels(&mut self) -> Vec<usize> {
+        let mut root_labels = vec![usize::MAX; self.len()];
+        let mut next = 0;
+        (0..self.len())
+            .map(|x| {
+                let root = self.find(x);
+                if root_labels[root] == usize::MAX {
+                    root_labels[root] = next;
+                    next += 1;
+                }
+                root_labels[root]
+            })
+            .collect()
+    }
+
+    /// Returns the indices of every set, each in ascending order.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let mut groups = vec![vec![]; self.sets];
+        for (x, label) in self.labels().into_iter().enumerate() {
+            groups[label].push(x);
+        }
+        groups
+    }
+}
+
+/// Labels the cells of a grid by the component they belong to, with neighbouring cells in the
+/// same component if `connected` returns `true` for them. Components are numbered from 0 in
+/// row-major order of their first cell.
+pub fn label_grid<T>(grid: &Grid<T>, mut connected: impl FnMut(&T, &T) -> bool) -> Grid<usize> {
+    let index = |c: Coordinate| c.y as usize * grid.width() + c.x as usize;
+    let mut sets = UnionFind::new(grid.width() * grid.height());
+    for (c, cell) in grid.iter() {
+        for neighbour in [c + Coordinate::new(1, 0), c + Coordinate::new(0, 1)] {
+            if grid.get(neighbour).is_some_and(|n| connected(cell, n)) {
+                sets.union(index(c), index(neighbour));
+            }
+        }
+    }
+    Grid::from_vec(grid.width(), sets.labels())
+}
+
+/// Returns the cells of every component of a grid, as described for [`label_grid`].
+pub fn grid_components<T>(
+    grid: &Grid<T>,
+    connected: impl FnMut(&T, &T) -> bool,
+) -> Vec<Vec<Coordinate>> {
+    let mut components = vec![];
+    for (c, &label) in label_grid(grid, connected).iter() {
+        if label == components.len() {
+            components.push(vec![]);
+        }
+        components[label].push(c);
+    }
+    components
+}
+
+/// Returns the nodes of every component of the graph with `nodes` and undirected `edges`, in the
+/// order the nodes first appear. Nodes only appearing in edges are included.
+pub fn graph_components<N: Copy + Eq + Hash>(
+    nodes: impl IntoIterator<Item = N>,
+    edges: impl IntoIterator<Item = (N, N)>,
+) -> Vec<Vec<N>> {
+    let mut indices = HashMap::default();
+    let mut all_nodes = vec![];
+    let mut index = |node: N| {
+        *indices.entry(node).or_insert_with(|| {
+            all_nodes.push(node);
+            all_nodes.len() - 1
+        })
+    };
+    nodes.into_iter().for_each(|node| {
+        index(node);
+    });
+    let edges: Vec<(usize, usize)> = edges
+        .into_iter()
+        .map(|(a, b)| (index(a), index(b)))
+        .collect();
+
+    let mut sets = UnionFind::new(all_nodes.len());
+    edges.into_iter().for_each(|(a, b)| {
+        sets.union(a, b);
+    });
+    sets.groups()
+        .into_iter()
+        .map(|group| group.into_iter().map(|i| all_nodes[i]).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.size(2), 4);
+        assert_eq!(sets.size(5), 1);
+        assert_eq!(sets.sets(), 3);
+        assert_eq!(sets.labels(), [0, 0, 0, 0, 1, 2]);
+        assert_eq!(sets.groups(), [vec![0, 1, 2, 3], vec![4], vec![5]]);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let mut sets = UnionFind::new(100_000);
+        (1..sets.len()).for_each(|i| {
+            sets.union(i - 1, i);
+        });
+        assert_eq!(sets.size(0), 100_000);
+        assert_eq!(sets.sets(), 1);
+    }
+
+    #[test]
+    fn test_grid_components() {
+        let grid = Grid::parse("AAB\nCAB\nCCA", |c| c);
+        assert_eq!(
+            label_grid(&grid, |a, b| a == b),
+            Grid::from_vec(3, vec![0, 0, 1, 2, 0, 1, 2, 2, 3])
+        );
+        let components = grid_components(&grid, |a, b| a == b);
+        assert_eq!(components.len(), 4);
+        assert_eq!(
+            components[2],
+            [(0, 1), (0, 2), (1, 2)].map(Coordinate::from)
+        );
+    }
+
+    #[test]
+    fn test_graph_components() {
+        let components = graph_components(["a", "b", "c", "d"], [("a", "c"), ("e", "d")]);
+        assert_eq!(components, [vec!["a", "c"], vec!["b"], vec!["d", "e"]]);
+    }
+}
@@ -0,0 +1,426 @@
+//! Pictures of puzzle states for debugging: coloured text for the terminal, and PPM or PNG images.
+//! Simulations write [`Frames`] into the directory given to `aoc --viz DIR`, which can be turned
+//! into a video with e.g. `ffmpeg -i 2022-14-part1-%05d.png sand.mp4`.
+
+use crate::coordinate::{Bounds, Coordinate};
+use crate::grid::Grid;
+use crate::sparsegrid::SparseGrid;
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+#[derive(Copy, Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Self = Self(0, 0, 0);
+    pub const WHITE: Self = Self(255, 255, 255);
+    pub const GREY: Self = Self(128, 128, 128);
+    pub const RED: Self = Self(220, 50, 47);
+    pub const GREEN: Self = Self(0, 153, 0);
+    pub const BLUE: Self = Self(38, 139, 210);
+    pub const YELLOW: Self = Self(255, 204, 0);
+    pub const SAND: Self = Self(194, 178, 128);
+    pub const BROWN: Self = Self(139, 90, 43);
+}
+
+/// A rectangular picture with the origin in the top left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Creates an image with every pixel set to `background`.
+    pub fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    /// Creates an image of the area within `bounds`, with a pixel per coordinate coloured by `f`.
+    pub fn from_fn(bounds: Bounds, mut f: impl FnMut(Coordinate) -> Rgb) -> Self {
+        Self {
+            width: bounds.width(),
+            height: bounds.height(),
+            pixels: bounds.coordinates().map(&mut f).collect(),
+        }
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, mut f: impl FnMut(&T) -> Rgb) -> Self {
+        Self::from_fn(Bounds::from_size(grid.width(), grid.height()), |c| {
+            f(&grid[c])
+        })
+    }
+
+    /// Creates an image of the bounds of a sparse grid, passing `None` to `f` for empty cells.
+    pub fn from_sparse<T>(grid: &SparseGrid<T>, mut f: impl FnMut(Option<&T>) -> Rgb) -> Self {
+        Self::from_fn(grid.bounds(), |c| f(grid.get(c)))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the colour at `c`, if it's within the image.
+    pub fn get(&self, c: Coordinate) -> Option<Rgb> {
+        self.index(c).map(|i| self.pixels[i])
+    }
+
+    /// Sets the colour at `c`, ignoring coordinates outside the image.
+    pub fn set(&mut self, c: Coordinate, colour: Rgb) {
+        if let Some(i) = self.index(c) {
+            self.pixels[i] = colour;
+        }
+    }
+
+    fn index(&self, c: Coordinate) -> Option<usize> {
+        Bounds::from_size(self.width, self.height)
+            .contains(c)
+            .then(|| c.y as usize * self.width + c.x as usize)
+    }
+
+    /// Returns a copy with every pixel blown up to a `factor` by `factor` square.
+    pub fn scaled(&self, factor: usize) -> Self {
+        let pixels = self
+            .pixels
+            .chunks(self.width.max(1))
+            .flat_map(|row| {
+                let row: Vec<Rgb> = row
+                    .iter()
+                    .flat_map(|&p| std::iter::repeat_n(p, factor))
+                    .collect();
+                std::iter::repeat_n(row, factor).flatten()
+            })
+            .collect();
+        Self {
+            width: self.width * factor,
+            height: self.height * factor,
+            pixels,
+        }
+    }
+
+    /// Renders the image with 24-bit ANSI colours, two pixel rows per line of half blocks.
+    pub fn terminal(&self) -> String {
+        let mut text = String::new();
+        for y in (0..self.height).step_by(2) {
+            for x in 0..self.width {
+                let Rgb(r, g, b) = self.pixels[y * self.width + x];
+                let Rgb(r2, g2, b2) = self
+                    .pixels
+                    .get((y + 1) * self.width + x)
+                    .filter(|_| y + 1 < self.height)
+                    .copied()
+                    .unwrap_or_default();
+                write!(text, "\x1b[38;2;{r};{g};{b}m\x1b[48;2;{r2};{g2};{b2}m▀").unwrap();
+            }
+            text.push_str("\x1b[0m\n");
+        }
+        text
+    }
+
+    /// Encodes the image as a binary PPM, which is trivial to write but big.
+    pub fn ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        bytes.extend(self.pixels.iter().flat_map(|&Rgb(r, g, b)| [r, g, b]));
+        bytes
+    }
+
+    /// Encodes the image as an 8-bit RGB PNG.
+    pub fn png(&self) -> Vec<u8> {
+        let mut scanlines = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            // Filter type 0, i.e. none. Repeated pixels and rows compress well enough as they are.
+            scanlines.push(0);
+            scanlines.extend(row.iter().flat_map(|&Rgb(r, g, b)| [r, g, b]));
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // Bit depth 8, colour type RGB, default compression, filtering and no interlacing.
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut bytes = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut bytes, b"IHDR", &header);
+        png_chunk(&mut bytes, b"IDAT", &zlib(&scanlines, self.width * 3 + 1));
+        png_chunk(&mut bytes, b"IEND", &[]);
+        bytes
+    }
+
+    /// Writes the image to `path`, as a PPM if its extension is `ppm` and a PNG otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|e| e == "ppm") {
+            fs::write(path, self.ppm())
+        } else {
+            fs::write(path, self.png())
+        }
+    }
+}
+
+fn png_chunk(bytes: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    bytes.extend((data.len() as u32).to_be_bytes());
+    let start = bytes.len();
+    bytes.extend(kind);
+    bytes.extend(data);
+    let crc = crc32(&bytes[start..]);
+    bytes.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1, 0), |(a, b), &byte| {
+        let a = (a + byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    (b << 16) | a
+}
+
+/// Compresses `data` into a zlib stream of a single fixed Huffman block. It only looks for
+/// repeats of the previous pixel and of the same bytes one `row_length` back, which is where
+/// pictures of puzzles repeat themselves.
+fn zlib(data: &[u8], row_length: usize) -> Vec<u8> {
+    const MAX_DISTANCE: usize = 32768;
+    const MAX_LENGTH: usize = 258;
+
+    let mut out = BitWriter::default();
+    // Deflate with a 32K window, no preset dictionary, and the least compression level.
+    out.bytes.extend([0x78, 0x01]);
+    // The final block, compressed with fixed codes.
+    out.bits(1, 1);
+    out.bits(1, 2);
+
+    let distances = [3, row_length].map(|d| Some(d).filter(|&d| d <= MAX_DISTANCE));
+    let mut i = 0;
+    while i < data.len() {
+        let best = distances
+            .into_iter()
+            .flatten()
+            .filter(|&distance| distance <= i)
+            .map(|distance| {
+                let length = (0..MAX_LENGTH.min(data.len() - i))
+                    .take_while(|&k| data[i + k] == data[i + k - distance])
+                    .count();
+                (length, distance)
+            })
+            .max();
+        match best {
+            Some((length, distance)) if length >= 3 => {
+                out.length(length);
+                out.distance(distance);
+                i += length;
+            }
+            _ => {
+                out.literal(data[i] as u16);
+                i += 1;
+            }
+        }
+    }
+    // End of block.
+    out.literal(256);
+
+    let mut bytes = out.finish();
+    bytes.extend(adler32(data).to_be_bytes());
+    bytes
+}
+
+/// Writes deflate's bit stream, which packs values from the least significant bit up.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn bits(&mut self, value: u32, count: u32) {
+        self.buffer |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    /// Writes a Huffman code, which goes most significant bit first.
+    fn code(&mut self, code: u32, count: u32) {
+        self.bits(code.reverse_bits() >> (32 - count), count);
+    }
+
+    /// Writes a literal byte or a length symbol with the fixed literal/length code.
+    fn literal(&mut self, symbol: u16) {
+        let symbol = symbol as u32;
+        match symbol {
+            0..=143 => self.code(0x30 + symbol, 8),
+            144..=255 => self.code(0x190 + symbol - 144, 9),
+            256..=279 => self.code(symbol - 256, 7),
+            _ => self.code(0xc0 + symbol - 280, 8),
+        }
+    }
+
+    fn length(&mut self, length: usize) {
+        const BASES: [usize; 29] = [
+            3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99,
+            115, 131, 163, 195, 227, 258,
+        ];
+        const EXTRA: [u32; 29] = [
+            0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+        ];
+        let code = BASES.partition_point(|&base| base <= length) - 1;
+        self.literal(257 + code as u16);
+        self.bits((length - BASES[code]) as u32, EXTRA[code]);
+    }
+
+    fn distance(&mut self, distance: usize) {
+        const BASES: [usize; 30] = [
+            1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025,
+            1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+        ];
+        let code = BASES.partition_point(|&base| base <= distance) - 1;
+        self.code(code as u32, 5);
+        self.bits(
+            (distance - BASES[code]) as u32,
+            code.saturating_sub(2) as u32 / 2,
+        );
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+static FRAME_DIRECTORY: OnceLock<PathBuf> = OnceLock::new();
+
+/// Makes every [`Frames`] created from now on write its images into `directory`.
+pub fn record_frames(directory: impl Into<PathBuf>) {
+    FRAME_DIRECTORY
+        .set(directory.into())
+        .expect("already recording frames");
+}
+
+/// A numbered sequence of images of a simulation, written as PNGs named after it. Does nothing
+/// unless [`record_frames`] was called, so simulations can record unconditionally.
+pub struct Frames {
+    name: String,
+    next: usize,
+}
+
+impl Frames {
+    /// Creates a sequence named e.g. `2022-14-part1`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            next: 0,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        FRAME_DIRECTORY.get().is_some()
+    }
+
+    /// Writes the next frame, only drawing it with `draw` if frames are being recorded.
+    pub fn record(&mut self, draw: impl FnOnce() -> Image) {
+        let Some(directory) = FRAME_DIRECTORY.get() else {
+            return;
+        };
+        let path = directory.join(format!("{}-{:05}.png", self.name, self.next));
+        draw().save(&path).expect("failed to write frame");
+        self.next += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkerboard() -> Image {
+        Image::from_fn(Bounds::from_size(3, 3), |c| {
+            if (c.x + c.y) % 2 == 0 {
+                Rgb::BLACK
+            } else {
+                Rgb::WHITE
+            }
+        })
+    }
+
+    #[test]
+    fn test_image() {
+        let mut image = checkerboard();
+        assert_eq!(image.get(Coordinate::new(1, 0)), Some(Rgb::WHITE));
+        assert_eq!(image.get(Coordinate::new(3, 0)), None);
+        image.set(Coordinate::new(2, 2), Rgb::RED);
+        image.set(Coordinate::new(-1, 2), Rgb::RED);
+        let scaled = image.scaled(2);
+        assert_eq!((scaled.width(), scaled.height()), (6, 6));
+        assert_eq!(scaled.get(Coordinate::new(5, 4)), Some(Rgb::RED));
+        assert_eq!(scaled.get(Coordinate::new(2, 1)), Some(Rgb::WHITE));
+
+        let sparse: SparseGrid<()> = [Coordinate::new(-1, 0), Coordinate::new(1, 0)]
+            .into_iter()
+            .collect();
+        let image = Image::from_sparse(&sparse, |c| c.map_or(Rgb::BLACK, |_| Rgb::WHITE));
+        assert_eq!(image.pixels, [Rgb::WHITE, Rgb::BLACK, Rgb::WHITE]);
+    }
+
+    #[test]
+    fn test_terminal() {
+        let text = Image::from_grid(&Grid::parse("#\n.\n#", |c| c == '#'), |&wall| {
+            if wall {
+                Rgb::WHITE
+            } else {
+                Rgb::BLACK
+            }
+        })
+        .terminal();
+        assert_eq!(
+            text,
+            "\x1b[38;2;255;255;255m\x1b[48;2;0;0;0m▀\x1b[0m\n\
+             \x1b[38;2;255;255;255m\x1b[48;2;0;0;0m▀\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn test_ppm() {
+        let image = Image::new(2, 1, Rgb(1, 2, 3));
+        assert_eq!(image.ppm(), b"P6\n2 1\n255\n\x01\x02\x03\x01\x02\x03");
+    }
+
+    #[test]
+    fn test_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_png() {
+        let png = checkerboard().scaled(100).png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\x01\x2c\0\0\x01\x2c"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+        // 270,300 bytes of pixels, mostly repeats.
+        assert!(png.len() < 3000);
+    }
+}
@@ -3,10 +3,14 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/01");
+    let (pt1, pt2) = run(include_str!("inputs/01"));
+    print_results(2015, 1, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let pt1 = part_1(input);
     let pt2 = part_2(input);
-    print_results(2015, 1, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn part_1(input: &str) -> i32 {
@@ -6,11 +6,15 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/02");
+    let (pt1, pt2) = run(include_str!("inputs/02"));
+    print_results(2015, 2, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let parsed = parse(input);
     let pt1 = part_1(&parsed);
     let pt2 = part_2(&parsed);
-    print_results(2015, 2, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn parse(s: &str) -> Vec<(u32, u32, u32)> {
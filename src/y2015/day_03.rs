@@ -1,36 +1,40 @@
 use crate::coordinate::Coordinate;
+use crate::sparsegrid::SparseGrid;
 use crate::{coordinate, print_results};
-use rustc_hash::FxHashSet;
 use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/03");
+    let (pt1, pt2) = run(include_str!("inputs/03"));
+    print_results(2015, 3, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let pt1 = part_1(input);
     let pt2 = part_2(input);
-    print_results(2015, 3, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn part_1(input: &str) -> usize {
     let mut position = Coordinate::default();
-    let mut visited = FxHashSet::from_iter([Coordinate::default()]);
+    let mut visited: SparseGrid<()> = [position].into_iter().collect();
     for c in input.chars() {
         position += direction(c);
-        visited.insert(position);
+        visited.insert(position, ());
     }
     visited.len()
 }
 
 fn part_2(input: &str) -> usize {
     let (mut santa, mut robo_santa) = (Coordinate::default(), Coordinate::default());
-    let mut visited = FxHashSet::from_iter([Coordinate::default()]);
+    let mut visited: SparseGrid<()> = [santa].into_iter().collect();
     for c in input.chars().step_by(2) {
         santa += direction(c);
-        visited.insert(santa);
+        visited.insert(santa, ());
     }
     for c in input.chars().skip(1).step_by(2) {
         robo_santa += direction(c);
-        visited.insert(robo_santa);
+        visited.insert(robo_santa, ());
     }
     visited.len()
 }
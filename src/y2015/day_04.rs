@@ -4,8 +4,13 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let secret = include_str!("inputs/04").trim();
-    print_results(2015, 4, mine(secret, 5), mine(secret, 6), Some(start));
+    let (pt1, pt2) = run(include_str!("inputs/04"));
+    print_results(2015, 4, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let secret = input.trim();
+    (mine(secret, 5).to_string(), mine(secret, 6).to_string())
 }
 
 fn mine(secret: &str, leading_zeroes: usize) -> usize {
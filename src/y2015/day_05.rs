@@ -4,14 +4,15 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/05");
-    print_results(
-        2015,
-        5,
-        input.lines().filter(|s| is_nice(s)).count(),
-        input.lines().filter(|s| is_nice_v2(s)).count(),
-        Some(start),
-    );
+    let (pt1, pt2) = run(include_str!("inputs/05"));
+    print_results(2015, 5, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (
+        input.lines().filter(|s| is_nice(s)).count().to_string(),
+        input.lines().filter(|s| is_nice_v2(s)).count().to_string(),
+    )
 }
 
 fn is_nice(s: &str) -> bool {
@@ -1,4 +1,5 @@
-use crate::coordinate::Coordinate;
+use crate::bitgrid::BitGrid;
+use crate::coordinate::{Bounds, Coordinate};
 use crate::print_results;
 use itertools::Itertools;
 use nom::branch::alt;
@@ -11,40 +12,34 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/06");
+    let (pt1, pt2) = run(include_str!("inputs/06"));
+    print_results(2015, 6, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let instructions = parse_instructions(input);
-    print_results(
-        2015,
-        6,
-        part_1(&instructions),
-        part_2(&instructions),
-        Some(start),
-    );
+    (
+        part_1(&instructions).to_string(),
+        part_2(&instructions).to_string(),
+    )
 }
 
 fn part_1(instructions: &[Instruction]) -> usize {
     instructions
         .iter()
         .fold(
-            [false; 1000 * 1000],
+            BitGrid::new(1000, 1000),
             |mut state, Instruction { from, to, op }| {
-                let change = match op {
-                    Op::On => |_| true,
-                    Op::Off => |_| false,
-                    Op::Toggle => |val| val ^ true,
-                };
-                for x in from.x..=to.x {
-                    for y in from.y..=to.y {
-                        state[1000 * x as usize + y as usize] =
-                            change(state[1000 * x as usize + y as usize]);
-                    }
+                let area = Bounds::spanning(*from, *to);
+                match op {
+                    Op::On => state.set_area(area, true),
+                    Op::Off => state.set_area(area, false),
+                    Op::Toggle => state.toggle_area(area),
                 }
                 state
             },
         )
-        .iter()
-        .filter(|x| **x)
-        .count()
+        .count_ones()
 }
 
 fn part_2(instructions: &[Instruction]) -> u32 {
@@ -58,11 +53,9 @@ fn part_2(instructions: &[Instruction]) -> u32 {
                     Op::Off => |val: u32| val.saturating_sub(1),
                     Op::Toggle => |val| val + 2,
                 };
-                for x in from.x..=to.x {
-                    for y in from.y..=to.y {
-                        state[1000 * x as usize + y as usize] =
-                            change(state[1000 * x as usize + y as usize]);
-                    }
+                for c in Bounds::spanning(*from, *to).coordinates() {
+                    let idx = 1000 * c.y as usize + c.x as usize;
+                    state[idx] = change(state[idx]);
                 }
                 state
             },
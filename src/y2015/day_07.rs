@@ -1,71 +1,75 @@
+use crate::interner::Interner;
 use crate::print_results;
-use ahash::HashMap;
 use itertools::Itertools;
 use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/07");
-    let mut mappings = parse(input);
-    let mut cache = HashMap::default();
-    let pt1 = resolve(&mut cache, &mappings, "a");
-    let mut cache = HashMap::default();
-    mappings.insert("b", Gate::Just(Value::Signal(pt1)));
-    let pt2 = resolve(&mut cache, &mappings, "a");
+    let (pt1, pt2) = run(include_str!("inputs/07"));
     print_results(2015, 7, pt1, pt2, Some(start));
 }
 
-fn resolve<'a>(
-    cache: &mut HashMap<&'a str, u16>,
-    mappings: &'a HashMap<&'a str, Gate>,
-    wire: &'a str,
-) -> u16 {
-    if let Some(v) = cache.get(wire) {
-        return *v;
+pub fn run(input: &str) -> (String, String) {
+    let (wires, mut circuit) = parse(input);
+    let a = wires.get("a").expect("wire a not found");
+    let pt1 = resolve(&mut vec![None; circuit.len()], &circuit, a);
+    let b = wires.get("b").expect("wire b not found");
+    circuit[b as usize] = Some(Gate::Just(Value::Signal(pt1)));
+    let pt2 = resolve(&mut vec![None; circuit.len()], &circuit, a);
+    (pt1.to_string(), pt2.to_string())
+}
+
+/// The gate driving each wire, indexed by wire id.
+type Circuit = Vec<Option<Gate>>;
+
+fn resolve(cache: &mut [Option<u16>], circuit: &[Option<Gate>], wire: u16) -> u16 {
+    if let Some(v) = cache[wire as usize] {
+        return v;
     }
 
     let mut resolve_value = |v| match v {
         Value::Signal(v) => v,
-        Value::Wire(v) => resolve(cache, mappings, v),
+        Value::Wire(v) => resolve(cache, circuit, v),
     };
 
-    let rv = match mappings.get(wire) {
-        Some(Gate::Just(v)) => resolve_value(*v),
-        Some(Gate::And(a, b)) => resolve_value(*a) & resolve_value(*b),
-        Some(Gate::Or(a, b)) => resolve_value(*a) | resolve_value(*b),
-        Some(Gate::Not(v)) => !resolve_value(*v),
-        Some(Gate::LShift(v, by)) => resolve_value(*v) << by,
-        Some(Gate::RShift(v, by)) => resolve_value(*v) >> by,
-        _ => panic!("wire not found: {wire}"),
+    let rv = match circuit[wire as usize] {
+        Some(Gate::Just(v)) => resolve_value(v),
+        Some(Gate::And(a, b)) => resolve_value(a) & resolve_value(b),
+        Some(Gate::Or(a, b)) => resolve_value(a) | resolve_value(b),
+        Some(Gate::Not(v)) => !resolve_value(v),
+        Some(Gate::LShift(v, by)) => resolve_value(v) << by,
+        Some(Gate::RShift(v, by)) => resolve_value(v) >> by,
+        None => panic!("wire not connected: {wire}"),
     };
-    cache.insert(wire, rv);
+    cache[wire as usize] = Some(rv);
     rv
 }
 
 #[derive(Debug, Clone, Copy)]
-enum Value<'a> {
-    Wire(&'a str),
+enum Value {
+    Wire(u16),
     Signal(u16),
 }
 
 #[derive(Debug, Clone, Copy)]
-enum Gate<'a> {
-    Just(Value<'a>),
-    And(Value<'a>, Value<'a>),
-    Or(Value<'a>, Value<'a>),
-    Not(Value<'a>),
-    LShift(Value<'a>, usize),
-    RShift(Value<'a>, usize),
+enum Gate {
+    Just(Value),
+    And(Value, Value),
+    Or(Value, Value),
+    Not(Value),
+    LShift(Value, usize),
+    RShift(Value, usize),
 }
 
-fn parse_value(s: &str) -> Value {
-    s.parse::<u16>()
-        .map(Value::Signal)
-        .unwrap_or(Value::Wire(s))
-}
-
-fn parse(s: &str) -> HashMap<&str, Gate> {
-    s.lines()
+fn parse<'a>(s: &'a str) -> (Interner<'a, u16>, Circuit) {
+    let mut wires = Interner::new();
+    let mut parse_value = |s: &'a str| {
+        s.parse::<u16>()
+            .map(Value::Signal)
+            .unwrap_or_else(|_| Value::Wire(wires.intern(s)))
+    };
+    let gates = s
+        .lines()
         .map(|l| {
             let parts = l.split_whitespace().collect_vec();
             match parts.len() {
@@ -90,7 +94,16 @@ fn parse(s: &str) -> HashMap<&str, Gate> {
                 _ => panic!("invalid input"),
             }
         })
-        .collect()
+        .collect_vec();
+    let targets = gates
+        .iter()
+        .map(|(wire, _)| wires.intern(wire))
+        .collect_vec();
+    let mut circuit = vec![None; wires.len()];
+    for (wire, (_, gate)) in targets.into_iter().zip(gates) {
+        circuit[wire as usize] = Some(gate);
+    }
+    (wires, circuit)
 }
 
 #[cfg(test)]
@@ -108,7 +121,7 @@ x LSHIFT 2 -> f
 y RSHIFT 2 -> g
 NOT x -> h
 NOT y -> i";
-        let mappings = parse(input);
+        let (wires, circuit) = parse(input);
         for (k, v) in [
             ("d", 72),
             ("e", 507),
@@ -119,7 +132,8 @@ NOT y -> i";
             ("x", 123),
             ("y", 456),
         ] {
-            assert_eq!(resolve(&mut HashMap::default(), &mappings, k), v);
+            let wire = wires.get(k).unwrap();
+            assert_eq!(resolve(&mut vec![None; circuit.len()], &circuit, wire), v);
         }
     }
 }
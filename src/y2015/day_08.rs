@@ -10,13 +10,17 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/08");
+    let (pt1, pt2) = run(include_str!("inputs/08"));
+    print_results(2015, 8, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let pt1 = input.lines().map(|l| l.len() - str_len(l)).sum::<usize>();
     let pt2 = input
         .lines()
         .map(|l| 2 + l.chars().filter(|c| "\\\"".contains(*c)).count())
         .sum::<usize>();
-    print_results(2015, 8, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn str_len(s: &str) -> usize {
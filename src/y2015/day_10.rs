@@ -4,11 +4,16 @@ use std::{iter, time::Instant};
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/10").trim();
+    let (pt1, pt2) = run(include_str!("inputs/10"));
+    print_results(2015, 10, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let input = input.trim();
     let mut solver = iter::successors(Some(input.to_string()), |prev| Some(transform(prev)));
     let pt1 = solver.nth(40).unwrap().len();
     let pt2 = solver.nth(9).unwrap().len();
-    print_results(2015, 10, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn transform(s: &str) -> String {
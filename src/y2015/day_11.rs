@@ -4,16 +4,21 @@ use std::{iter, time::Instant};
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/11").trim();
+    let (pt1, pt2) = run(include_str!("inputs/11"));
+    print_results(2015, 11, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let input = input.trim();
     let pt1 = next_password(input);
     let pt2 = next_password(&pt1);
-    print_results(2015, 11, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn next_password(current: &str) -> String {
     let mut solver = iter::successors(Some(increment(current)), |prev| Some(increment(prev)));
     solver
-        .find(|p| is_valid(&p))
+        .find(|p| is_valid(p))
         .expect("no valid password")
         .to_string()
 }
@@ -5,12 +5,16 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/12");
+    let (pt1, pt2) = run(include_str!("inputs/12"));
+    print_results(2015, 12, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let mut input_bytes = input.bytes().collect_vec();
     let parsed = to_borrowed_value(&mut input_bytes).expect("failed to parse input");
     let pt1 = find_numbers(&parsed).iter().sum::<i64>();
     let pt2 = find_non_red_numbers(&parsed).iter().sum::<i64>();
-    print_results(2015, 12, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn find_numbers(val: &Value) -> Vec<i64> {
@@ -1,43 +1,40 @@
+use crate::graph::{self, IndexedGraph};
 use crate::print_results;
-use ahash::HashMap;
 use itertools::Itertools;
-use std::iter;
 use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/13");
+    let (pt1, pt2) = run(include_str!("inputs/13"));
+    print_results(2015, 13, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let map = build_map(input);
     let pt1 = optimal_arrangment(&map);
-    let pt2_map = map
-        .iter()
-        .map(|(&k, v)| {
-            let mut v = v.clone();
-            v.insert("me", 0);
-            (k, v)
-        })
-        .chain(iter::once(("me", map.keys().map(|&k| (k, 0)).collect())))
-        .collect();
+    let mut pt2_map = map.clone();
+    for &guest in map.nodes() {
+        pt2_map.connect("me", guest, 0);
+    }
     let pt2 = optimal_arrangment(&pt2_map);
-    print_results(2015, 13, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
-fn optimal_arrangment(map: &HashMap<&str, HashMap<&str, i32>>) -> i32 {
-    map.keys()
-        .permutations(map.len())
-        .map(|order| {
-            order
-                .iter()
-                .circular_tuple_windows()
-                .map(|(a, b)| map[*a][*b] + map[*b][*a])
-                .sum()
+fn optimal_arrangment(map: &IndexedGraph<&str, i32>) -> i32 {
+    // Sitting next to each other affects both guests, so combine both directions.
+    let happiness = map.weights();
+    let pairs: Vec<Vec<_>> = (0..map.len())
+        .map(|a| {
+            (0..map.len())
+                .map(|b| Some(happiness[a][b]? + happiness[b][a]?))
+                .collect()
         })
-        .max()
-        .expect("no solution")
+        .collect();
+    graph::longest_hamiltonian_cycle(&pairs).expect("no solution")
 }
 
-fn build_map(s: &str) -> HashMap<&str, HashMap<&str, i32>> {
-    let mut rv: HashMap<&str, HashMap<&str, i32>> = HashMap::default();
+fn build_map(s: &str) -> IndexedGraph<&str, i32> {
+    let mut rv = IndexedGraph::new();
     s.lines().for_each(|line| {
         let words = line
             .strip_suffix('.')
@@ -45,7 +42,8 @@ fn build_map(s: &str) -> HashMap<&str, HashMap<&str, i32>> {
             .split_whitespace()
             .collect_vec();
         let gain = if words[2] == "gain" { 1 } else { -1 };
-        rv.entry(words[0]).or_default().insert(
+        rv.add_edge(
+            words[0],
             words[10],
             words[3].parse::<i32>().expect("invalid happiness") * gain,
         );
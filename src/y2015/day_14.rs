@@ -4,7 +4,11 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/14");
+    let (pt1, pt2) = run(include_str!("inputs/14"));
+    print_results(2015, 14, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let reindeer = parse(input);
     let pt1 = reindeer
         .iter()
@@ -17,7 +21,7 @@ pub fn solve() {
         .into_values()
         .max()
         .unwrap();
-    print_results(2015, 14, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 struct Reindeer<'a> {
@@ -34,7 +38,7 @@ impl Reindeer<'_> {
     }
 }
 
-fn parse(s: &str) -> Vec<Reindeer> {
+fn parse(s: &str) -> Vec<Reindeer<'_>> {
     s.lines()
         .map(|l| {
             let words = l.split_whitespace().collect_vec();
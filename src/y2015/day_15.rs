@@ -8,11 +8,15 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/15");
+    let (pt1, pt2) = run(include_str!("inputs/15"));
+    print_results(2015, 15, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let ingredients = parse_ingredients(input);
     let pt1 = one_hundred_spoons(&ingredients);
     let pt2 = five_hundred_calories(&ingredients);
-    print_results(2015, 15, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn one_hundred_spoons(ingredients: &[Ingredient]) -> i32 {
@@ -4,7 +4,11 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/16");
+    let (pt1, pt2) = run(include_str!("inputs/16"));
+    print_results(2015, 16, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let aunts = parse_aunts(input);
     let search_key = HashMap::from_iter([
         ("children", 3),
@@ -40,7 +44,7 @@ pub fn solve() {
         })
         .expect("no aunt found")
         + 1;
-    print_results(2015, 16, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn parse_aunts(s: &str) -> Vec<HashMap<&str, u32>> {
@@ -4,7 +4,11 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/17");
+    let (pt1, pt2) = run(include_str!("inputs/17"));
+    print_results(2015, 17, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let containers = input
         .lines()
         .map(|l| l.parse::<u32>().unwrap())
@@ -19,5 +23,5 @@ pub fn solve() {
         .iter()
         .min_set_by_key(|cs| cs.len())
         .len();
-    print_results(2015, 17, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
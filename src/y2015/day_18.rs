@@ -1,51 +1,31 @@
-use crate::{coordinate::Coordinate, print_results};
-use ahash::HashMap;
+use crate::{bitgrid::BitGrid, print_results};
 use std::{iter::successors, time::Instant};
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/18");
-    let lights = Coordinate::parse_grid(input, |c| Some(c == '#'));
-    let pt1 = successors(Some(lights.clone()), |prev| Some(life(prev)))
+    let (pt1, pt2) = run(include_str!("inputs/18"));
+    print_results(2015, 18, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let lights = BitGrid::parse(input, |c| c == '#');
+    let pt1 = successors(Some(lights.clone()), |prev| Some(prev.life()))
         .nth(100)
         .unwrap()
-        .into_iter()
-        .filter(|(_, on)| *on)
-        .count();
+        .count_ones();
     let pt2 = successors(Some(turn_on_corners(lights)), |prev| {
-        Some(turn_on_corners(life(prev)))
+        Some(turn_on_corners(prev.life()))
     })
     .nth(100)
     .unwrap()
-    .into_iter()
-    .filter(|(_, on)| *on)
-    .count();
-    print_results(2015, 18, pt1, pt2, Some(start));
-}
-
-fn life(lights: &HashMap<Coordinate, bool>) -> HashMap<Coordinate, bool> {
-    let mut rv = lights.to_owned();
-    for (coord, already_on) in rv.iter_mut() {
-        let neighbours_on = coord
-            .diagonal_neighbours(0..100)
-            .into_iter()
-            .filter(|c| *lights.get(c).unwrap())
-            .count();
-        if *already_on && !(2..=3).contains(&neighbours_on) {
-            *already_on = false;
-        } else if !*already_on && neighbours_on == 3 {
-            *already_on = true;
-        }
-    }
-    rv
+    .count_ones();
+    (pt1.to_string(), pt2.to_string())
 }
 
-fn turn_on_corners(mut lights: HashMap<Coordinate, bool>) -> HashMap<Coordinate, bool> {
-    [(0, 0), (0, 99), (99, 0), (99, 99)]
+fn turn_on_corners(mut lights: BitGrid) -> BitGrid {
+    let (max_x, max_y) = (lights.width() as isize - 1, lights.height() as isize - 1);
+    [(0, 0), (0, max_y), (max_x, 0), (max_x, max_y)]
         .into_iter()
-        .map(Coordinate::from)
-        .for_each(|c| {
-            *lights.get_mut(&c).unwrap() = true;
-        });
+        .for_each(|c| lights.set(c.into(), true));
     lights
 }
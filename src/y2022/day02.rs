@@ -1,6 +1,10 @@
 pub fn solve() {
-    let input = include_str!("inputs/02.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/02.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 #[allow(clippy::identity_op)]
@@ -1,7 +1,11 @@
 use rustc_hash::{FxHashMap, FxHashSet};
 
 pub fn solve() {
-    let input = include_str!("inputs/03.txt");
+    let (part1, part2) = run(include_str!("inputs/03.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
     let rucksacks: Vec<&str> = input.lines().collect();
     let duplicates: Vec<char> = rucksacks
         .iter()
@@ -39,10 +43,9 @@ pub fn solve() {
         })
         .collect();
 
-    println!(
-        "{}\n{}",
-        duplicates.iter().map(get_priority).sum::<u32>(),
-        badges.iter().map(get_priority).sum::<u32>()
+    (
+        duplicates.iter().map(get_priority).sum::<u32>().to_string(),
+        badges.iter().map(get_priority).sum::<u32>().to_string(),
     )
 }
 
@@ -1,22 +1,30 @@
+use crate::interval::IntervalSet;
 use std::str::FromStr;
 
 pub fn solve() {
-    let input = include_str!("inputs/04.txt");
+    let (part1, part2) = run(include_str!("inputs/04.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
     let pairs: Vec<Pair> = input
         .lines()
         .map(|l| l.parse().expect("failed to parse pair"))
         .collect();
-    println!(
-        "{}\n{}",
-        pairs.iter().filter(|p| fully_contains(p)).count(),
-        pairs.iter().filter(|p| overlaps(p)).count()
+    (
+        pairs
+            .iter()
+            .filter(|p| fully_contains(p))
+            .count()
+            .to_string(),
+        pairs.iter().filter(|p| overlaps(p)).count().to_string(),
     )
 }
 
 #[derive(Debug)]
 struct Pair {
-    first: Range,
-    second: Range,
+    first: IntervalSet,
+    second: IntervalSet,
 }
 
 impl FromStr for Pair {
@@ -25,8 +33,8 @@ impl FromStr for Pair {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         if let Some((first, second)) = s.split_once(',') {
             Ok(Self {
-                first: first.parse()?,
-                second: second.parse()?,
+                first: parse_range(first)?,
+                second: parse_range(second)?,
             })
         } else {
             Err("invalid pair")
@@ -34,42 +42,21 @@ impl FromStr for Pair {
     }
 }
 
-#[derive(Debug)]
-struct Range {
-    start: u32,
-    end: u32,
-}
-
-impl FromStr for Range {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((start, end)) = s.split_once('-') {
-            Ok(Range {
-                start: start.parse().map_err(|_| "invalid range start")?,
-                end: end.parse().map_err(|_| "invalid range end")?,
-            })
-        } else {
-            Err("invalid range")
-        }
+/// Parses an inclusive range of sections like `2-4`.
+fn parse_range(s: &str) -> Result<IntervalSet, &'static str> {
+    if let Some((start, end)) = s.split_once('-') {
+        let start: i64 = start.parse().map_err(|_| "invalid range start")?;
+        let end: i64 = end.parse().map_err(|_| "invalid range end")?;
+        Ok(IntervalSet::from(start..end + 1))
+    } else {
+        Err("invalid range")
     }
 }
 
 fn fully_contains(pair: &Pair) -> bool {
-    let ax = pair.first.start;
-    let ay = pair.first.end;
-    let bx = pair.second.start;
-    let by = pair.second.end;
-    (ax <= bx && ay >= by) || (bx <= ax && by >= ay)
+    pair.first.is_subset(&pair.second) || pair.second.is_subset(&pair.first)
 }
 
 fn overlaps(pair: &Pair) -> bool {
-    let ax = pair.first.start;
-    let ay = pair.first.end;
-    let bx = pair.second.start;
-    let by = pair.second.end;
-    (bx >= ax && bx <= ay)
-        || (by >= ax && by <= ay)
-        || (ax >= bx && ax <= by)
-        || (ay >= bx && ay <= by)
+    !pair.first.intersection(&pair.second).is_empty()
 }
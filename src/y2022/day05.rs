@@ -1,7 +1,11 @@
 use std::str::FromStr;
 
 pub fn solve() {
-    let input = include_str!("inputs/05.txt");
+    let (part1, part2) = run(include_str!("inputs/05.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
     let stack_tops = |stacks: &Stacks| -> String {
         stacks
             .crates
@@ -28,7 +32,7 @@ pub fn solve() {
         .map(|m| m.parse::<Move>().expect("failed to parse move"))
         .try_for_each(|m| execute_move_9001(&mut stacks, &m))
         .expect("failed to execute moves");
-    println!("{part1}\n{}", stack_tops(&stacks))
+    (part1.to_string(), stack_tops(&stacks).to_string())
 }
 
 fn execute_move_9000(stacks: &mut Stacks, m: &Move) -> Result<(), &'static str> {
@@ -1,11 +1,18 @@
 use rustc_hash::FxHashSet;
 
 pub fn solve() {
-    let input = include_str!("inputs/06.txt");
-    println!(
-        "{}\n{}",
-        find_start(input, 4).expect("unable to find start"),
-        find_start(input, 14).expect("unable to find start")
+    let (part1, part2) = run(include_str!("inputs/06.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (
+        find_start(input, 4)
+            .expect("unable to find start")
+            .to_string(),
+        find_start(input, 14)
+            .expect("unable to find start")
+            .to_string(),
     )
 }
 
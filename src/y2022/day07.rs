@@ -2,7 +2,11 @@ use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 pub fn solve() {
-    let input = include_str!("inputs/07.txt");
+    let (part1, part2) = run(include_str!("inputs/07.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
     let entries: Vec<Entry> = input
         .split("$ ")
         .skip(1)
@@ -31,7 +35,7 @@ pub fn solve() {
         .find(|d| d.get_size() >= required_space)
         .expect("no directory is large enough")
         .get_size();
-    println!("{part1}\n{part2}")
+    (part1.to_string(), part2.to_string())
 }
 
 #[derive(Debug)]
@@ -1,138 +1,69 @@
-use std::str::FromStr;
+use crate::coordinate::Coordinate;
+use crate::grid::Grid;
+use itertools::Itertools;
 
 pub fn solve() {
-    let input = include_str!("inputs/08.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/08.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
-    let trees: Map = input.parse().expect("invalid map");
-    (0..trees.len()).filter(|&idx| trees.visible(idx)).count()
+    let trees = parse_trees(input);
+    trees.coordinates().filter(|&t| visible(&trees, t)).count()
 }
 
 fn part2(input: &str) -> u32 {
-    let trees: Map = input.parse().expect("invalid map");
-    (0..trees.len())
-        .map(|idx| trees.scenic_score(idx))
+    let trees = parse_trees(input);
+    trees
+        .coordinates()
+        .map(|t| scenic_score(&trees, t))
         .max()
         .unwrap()
 }
 
-#[derive(Debug)]
-struct Map {
-    size: usize,
-    inner: Vec<u8>,
+fn parse_trees(s: &str) -> Grid<u8> {
+    Grid::parse(s, |c| c.to_digit(10).expect("invalid tree") as u8)
 }
 
-impl FromStr for Map {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut size = 0;
-        let inner: Vec<u8> = s
-            .lines()
-            .flat_map(|l| -> Vec<u8> {
-                size += 1;
-                l.chars()
-                    .map(|c| c.to_digit(10).expect("invalid tree") as u8)
-                    .collect()
-            })
-            .collect();
-
-        Ok(Self { inner, size })
-    }
+/// Applies `f` to the trees between `tree` and the edges, looking left, right, up and down, each
+/// ordered from closest to furthest.
+fn sightlines<R>(
+    trees: &Grid<u8>,
+    tree: Coordinate,
+    mut f: impl FnMut(&mut dyn Iterator<Item = &u8>) -> R,
+) -> [R; 4] {
+    let (x, y) = (tree.x as usize, tree.y as usize);
+    let row = trees.row(y);
+    [
+        f(&mut row[..x].iter().rev()),
+        f(&mut row[x + 1..].iter()),
+        f(&mut trees.column(x).take(y).rev()),
+        f(&mut trees.column(x).skip(y + 1)),
+    ]
 }
 
-impl Map {
-    /// Returns the total size of the inner tree vec.
-    fn len(&self) -> usize {
-        self.size * self.size
-    }
-
-    /// For a given (x, y) tree coordinate, returns the index.
-    fn idx(&self, x: usize, y: usize) -> usize {
-        y * self.size + x
-    }
-
-    /// For a given index, returns the tree (x, y) coordinates.
-    fn coords(&self, idx: usize) -> (usize, usize) {
-        (idx.rem_euclid(self.size), idx / self.size)
-    }
-
-    /// Returns true if the tree at idx is visible from the outside.
-    fn visible(&self, idx: usize) -> bool {
-        let (x, y) = self.coords(idx);
-        // On the edge?
-        if x == 0 || y == 0 || x == self.size - 1 || y == self.size - 1 {
-            return true;
-        }
-        let this_tree = self.inner[idx];
-        // Same row - left
-        if self.inner[self.idx(0, y)..idx]
-            .iter()
-            .all(|&t| t < this_tree)
-        {
-            return true;
-        }
-        // Same row - right
-        if self.inner[idx + 1..self.idx(self.size, y)]
-            .iter()
-            .all(|&t| t < this_tree)
-        {
-            return true;
-        }
-        // Same column - top
-        if self
-            .inner
-            .iter()
-            .skip(x)
-            .step_by(self.size)
-            .take(y)
-            .all(|&t| t < this_tree)
-        {
-            return true;
-        }
-        // Same column - bottom
-        if self
-            .inner
-            .iter()
-            .skip(x)
-            .step_by(self.size)
-            .skip(y + 1)
-            .all(|&t| t < this_tree)
-        {
-            return true;
-        }
-        false
-    }
-
-    /// For a given index, returns that tree's scenic score.
-    fn scenic_score(&self, idx: usize) -> u32 {
-        let (x, y) = self.coords(idx);
-        let this_tree = self.inner[idx];
-
-        let view_distance = |trees: &mut dyn Iterator<Item = &u8>| -> u32 {
-            let mut last_tree = 0;
-            let view_until = trees
-                .take_while(|&t| {
-                    if *t < this_tree {
-                        true
-                    } else {
-                        last_tree = 1;
-                        false
-                    }
-                })
-                .count();
-            // Account for +1 unless we can see until the edge.
-            (view_until + last_tree) as u32
-        };
+/// Returns true if the tree is visible from the outside.
+fn visible(trees: &Grid<u8>, tree: Coordinate) -> bool {
+    let height = trees[tree];
+    sightlines(trees, tree, |line| {
+        line.max().is_none_or(|&tallest| tallest < height)
+    })
+    .contains(&true)
+}
 
-        let left = view_distance(&mut self.inner[self.idx(0, y)..idx].iter().rev());
-        let right = view_distance(&mut self.inner[idx + 1..self.idx(self.size, y)].iter());
-        let up = view_distance(&mut self.inner.iter().skip(x).step_by(self.size).take(y).rev());
-        let down = view_distance(&mut self.inner.iter().skip(x).step_by(self.size).skip(y + 1));
-        left * right * up * down
-    }
+/// Returns the tree's scenic score.
+fn scenic_score(trees: &Grid<u8>, tree: Coordinate) -> u32 {
+    let height = trees[tree];
+    // Count the blocking tree unless we can see until the edge.
+    sightlines(trees, tree, |line| {
+        line.take_while_inclusive(|&&t| t < height).count() as u32
+    })
+    .iter()
+    .product()
 }
 
 #[cfg(test)]
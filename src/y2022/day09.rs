@@ -1,9 +1,16 @@
-use rustc_hash::FxHashSet;
+use crate::coordinate::{Coordinate, DOWN, LEFT, RIGHT, UP};
+use crate::replay::Simulation;
+use crate::sparsegrid::SparseGrid;
+use crate::viz::{Image, Rgb};
 use std::str::FromStr;
 
 pub fn solve() {
-    let input = include_str!("inputs/09.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/09.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
@@ -24,6 +31,52 @@ fn part2(input: &str) -> usize {
     rope.tail_history.len()
 }
 
+pub fn replay() -> Box<dyn Simulation> {
+    let steps: Vec<Coordinate> = include_str!("inputs/09.txt")
+        .lines()
+        .flat_map(|l| l.parse::<Move>().expect("failed to parse move").steps())
+        .collect();
+    Box::new(Replay {
+        rope: Rope::new(10),
+        steps: steps.into_iter(),
+    })
+}
+
+/// The rope of part 2, moving its head one step at a time.
+struct Replay {
+    rope: Rope,
+    steps: std::vec::IntoIter<Coordinate>,
+}
+
+impl Simulation for Replay {
+    fn step(&mut self) -> bool {
+        self.steps
+            .next()
+            .map(|direction| self.rope.step(direction))
+            .is_some()
+    }
+
+    fn image(&self) -> Image {
+        let rope = &self.rope;
+        let bounds = rope
+            .knots
+            .iter()
+            .fold(rope.tail_history.bounds(), |b, &knot| b.including(knot));
+        let mut image = Image::from_fn(bounds, |c| {
+            if rope.tail_history.contains(c) {
+                Rgb::GREY
+            } else {
+                Rgb::BLACK
+            }
+        });
+        for (i, &knot) in rope.knots.iter().enumerate().rev() {
+            let colour = if i == 0 { Rgb::RED } else { Rgb::WHITE };
+            image.set(knot - bounds.min, colour);
+        }
+        image
+    }
+}
+
 enum Move {
     Up(u8),
     Down(u8),
@@ -31,6 +84,19 @@ enum Move {
     Right(u8),
 }
 
+impl Move {
+    /// Returns the direction of each single step the head makes.
+    fn steps(&self) -> impl Iterator<Item = Coordinate> {
+        let (direction, n) = match *self {
+            Move::Up(n) => (UP, n),
+            Move::Down(n) => (DOWN, n),
+            Move::Left(n) => (LEFT, n),
+            Move::Right(n) => (RIGHT, n),
+        };
+        std::iter::repeat_n(direction, n as usize)
+    }
+}
+
 impl FromStr for Move {
     type Err = &'static str;
 
@@ -45,106 +111,41 @@ impl FromStr for Move {
     }
 }
 
-type Knot = (i16, i16);
-
 struct Rope {
-    knots: Vec<Knot>,
-    tail_history: FxHashSet<Knot>,
+    knots: Vec<Coordinate>,
+    tail_history: SparseGrid<()>,
 }
 
 impl Rope {
     fn new(num_knots: usize) -> Self {
         assert!(num_knots > 0);
-        let mut tail_history = FxHashSet::default();
-        tail_history.insert((0, 0));
+        let start = Coordinate::default();
         Self {
-            knots: vec![(0, 0); num_knots],
-            tail_history,
+            knots: vec![start; num_knots],
+            tail_history: [start].into_iter().collect(),
         }
     }
 
     fn move_head(&mut self, m: &Move) {
-        match m {
-            Move::Up(n) => {
-                for _ in 0..*n {
-                    let head = self.knots.get_mut(0).unwrap();
-                    head.1 += 1;
-                    for idx in 0..self.knots.len() - 1 {
-                        let head = *self.knots.get(idx).unwrap();
-                        let tail = self.knots.get_mut(idx + 1).unwrap();
-                        move_tail(&head, tail);
-                    }
-                    self.tail_history.insert(*self.knots.last().unwrap());
-                }
-            }
-            Move::Down(n) => {
-                for _ in 0..*n {
-                    let head = self.knots.get_mut(0).unwrap();
-                    head.1 -= 1;
-                    for idx in 0..self.knots.len() - 1 {
-                        let head = *self.knots.get(idx).unwrap();
-                        let tail = self.knots.get_mut(idx + 1).unwrap();
-                        move_tail(&head, tail);
-                    }
-                    self.tail_history.insert(*self.knots.last().unwrap());
-                }
-            }
-            Move::Left(n) => {
-                for _ in 0..*n {
-                    let head = self.knots.get_mut(0).unwrap();
-                    head.0 -= 1;
-                    for idx in 0..self.knots.len() - 1 {
-                        let head = *self.knots.get(idx).unwrap();
-                        let tail = self.knots.get_mut(idx + 1).unwrap();
-                        move_tail(&head, tail);
-                    }
-                    self.tail_history.insert(*self.knots.last().unwrap());
-                }
-            }
-            Move::Right(n) => {
-                for _ in 0..*n {
-                    let head = self.knots.get_mut(0).unwrap();
-                    head.0 += 1;
-                    for idx in 0..self.knots.len() - 1 {
-                        let head = *self.knots.get(idx).unwrap();
-                        let tail = self.knots.get_mut(idx + 1).unwrap();
-                        move_tail(&head, tail);
-                    }
-                    self.tail_history.insert(*self.knots.last().unwrap());
-                }
-            }
-        }
+        m.steps().for_each(|direction| self.step(direction));
     }
-}
 
-/// Returns the two-dimensional distance between two knots.
-fn distance(head: Knot, tail: Knot) -> (i16, i16) {
-    (head.0 - tail.0, head.1 - tail.1)
+    /// Moves the head one step in `direction`, with the rest of the rope following.
+    fn step(&mut self, direction: Coordinate) {
+        self.knots[0] += direction;
+        for idx in 0..self.knots.len() - 1 {
+            let head = self.knots[idx];
+            move_tail(head, &mut self.knots[idx + 1]);
+        }
+        self.tail_history.insert(*self.knots.last().unwrap(), ());
+    }
 }
 
-/// Moves tail such that it is in a legal position relative to head.
-fn move_tail(head: &Knot, tail: &mut Knot) {
-    let (x, y) = distance(*head, *tail);
-    if x.abs() + y.abs() > 2 {
-        // Off in more than one direction, need to move diagnoally.
-        tail.0 += if x > 0 {
-            (x - 1).max(1)
-        } else {
-            (x + 1).min(-1)
-        };
-        tail.1 += if y > 0 {
-            (y - 1).max(1)
-        } else {
-            (y + 1).min(-1)
-        };
-    } else if x > 0 {
-        tail.0 += x - 1;
-    } else if x < 0 {
-        tail.0 += x + 1;
-    } else if y > 0 {
-        tail.1 += y - 1;
-    } else if y < 0 {
-        tail.1 += y + 1;
+/// Moves tail such that it is in a legal position relative to head,
+/// i.e. touching it, by stepping straight or diagonally towards it.
+fn move_tail(head: Coordinate, tail: &mut Coordinate) {
+    if head.chebyshev_distance(*tail) > 1 {
+        *tail += Coordinate::new((head.x - tail.x).signum(), (head.y - tail.y).signum());
     }
 }
 
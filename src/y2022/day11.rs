@@ -7,8 +7,12 @@ use nom::{
 };
 
 pub fn solve() {
-    let input = include_str!("inputs/11.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/11.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> u64 {
@@ -107,7 +111,7 @@ impl Monkey {
     }
 
     fn item_destination(&self, item: u64) -> usize {
-        if item % self.test == 0 {
+        if item.is_multiple_of(self.test) {
             self.if_true
         } else {
             self.if_false
@@ -1,74 +1,37 @@
 /// This solves backwards, walking the map from 'E' to 'S'/'a', to
 /// make part 2 much faster.
-use std::collections::VecDeque;
 use std::str::FromStr;
 
-use rustc_hash::FxHashSet;
+use crate::pathfinding::bfs;
 
 pub fn solve() {
-    let input = include_str!("inputs/12.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/12.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
     let map: Map = input.parse().expect("invalid map");
-    let mut walker = Walker::new(&map, 'S'.into());
-    walker.walk().expect("no route found")
+    walk(&map, 'S'.into()).expect("no route found")
 }
 
 fn part2(input: &str) -> usize {
     let map: Map = input.parse().expect("invalid map");
-    let mut walker = Walker::new(&map, 'a'.into());
-    walker.walk().expect("no route found")
-}
-
-#[derive(Debug)]
-struct Walker<'a> {
-    goal: u32,
-    map: &'a Map,
-    candidates: VecDeque<Vec<usize>>,
-    visited: FxHashSet<usize>,
+    walk(&map, 'a'.into()).expect("no route found")
 }
 
-impl<'a> Walker<'a> {
-    fn new(map: &'a Map, goal: u32) -> Self {
-        let mut candidates = VecDeque::new();
-        map.options(map.end)
-            .iter()
-            .for_each(|&o| candidates.push_front(vec![o]));
-        let visited = FxHashSet::default();
-        Self {
-            goal,
-            map,
-            candidates,
-            visited,
-        }
-    }
-
-    /// Breadth-first walk all possible paths, returning the distance
-    /// once the first one finds the end.
-    fn walk(&mut self) -> Option<usize> {
-        while let Some(path) = self.candidates.pop_back() {
-            let position = *path.last().unwrap();
-            if self.map.inner[position] == self.goal {
-                return Some(path.len());
-            }
-            if self.visited.contains(&position) {
-                continue;
-            }
-            self.visited.insert(position);
-            self.map
-                .options(position)
-                .iter()
-                .filter(|&idx| !path.contains(idx))
-                .for_each(|&o| {
-                    let mut p = path.clone();
-                    p.push(o);
-                    self.candidates.push_front(p);
-                });
-        }
-        None
-    }
+/// Breadth-first walk from the end, returning the distance once we
+/// find the goal.
+fn walk(map: &Map, goal: u32) -> Option<usize> {
+    bfs(
+        [map.end],
+        |idx| map.options(idx),
+        |idx| map.inner[idx] == goal,
+    )
+    .goal_cost()
 }
 
 #[derive(Clone, Debug)]
@@ -6,8 +6,12 @@ use nom::{
 };
 
 pub fn solve() {
-    let input = include_str!("inputs/13.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/13.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
@@ -60,7 +64,7 @@ enum Message {
 
 impl PartialOrd for Message {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(cmp_messages(self, other).unwrap_or(Ordering::Equal))
+        Some(self.cmp(other))
     }
 }
 
@@ -1,3 +1,6 @@
+use crate::coordinate::{polyline, Bounds, Coordinate};
+use crate::replay::Simulation;
+use crate::viz::{Frames, Image, Rgb};
 use nom::{
     bytes::complete::tag,
     character::complete::u16,
@@ -12,14 +15,21 @@ use nom::{
 /// but YMMV.
 const CAVE_DEPTH: usize = 255;
 
+/// How many units of sand to drop between frames of the visualization.
+const FRAME_INTERVAL: usize = 50;
+
 pub fn solve() {
-    let input = include_str!("inputs/14.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/14.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
     let mut cave = Cave::new(parse_paths(input));
-    cave.drop_sand_until_terminal()
+    cave.drop_sand_until_terminal(&mut Frames::new("2022-14-part1"))
 }
 
 fn part2(input: &str) -> usize {
@@ -29,14 +39,35 @@ fn part2(input: &str) -> usize {
         .map(|path| path.iter().map(|point| point.y).max().unwrap())
         .max()
         .unwrap();
-    paths.push(vec![Point { x: 0, y: floor }, Point { x: 1000, y: floor }]);
+    // Sand piles up no wider than a triangle with its tip at the source.
+    paths.push(vec![
+        Coordinate::new(500 - floor, floor),
+        Coordinate::new(500 + floor, floor),
+    ]);
     let mut cave = Cave::new(paths);
-    cave.drop_sand_until_terminal()
+    cave.drop_sand_until_terminal(&mut Frames::new("2022-14-part2"))
+}
+
+/// Drops the sand of part 1 one unit at a time.
+pub fn replay() -> Box<dyn Simulation> {
+    Box::new(Cave::new(parse_paths(include_str!("inputs/14.txt"))))
+}
+
+impl Simulation for Cave {
+    fn step(&mut self) -> bool {
+        !self.drop_one_unit_of_sand()
+    }
+
+    fn image(&self) -> Image {
+        self.image()
+    }
 }
 
 #[derive(Debug)]
 struct Cave {
     inner: [Cell; 1000 * CAVE_DEPTH],
+    /// The area containing the rock and the source of the sand.
+    bounds: Bounds,
 }
 
 impl Cave {
@@ -44,42 +75,46 @@ impl Cave {
     /// paths.
     fn new(paths: Vec<Path>) -> Self {
         let mut inner = [Cell::Empty; 1000 * CAVE_DEPTH];
+        let mut bounds = Bounds::spanning(SOURCE, SOURCE);
         for path in paths {
-            for i in 0..path.len() - 1 {
-                let start = &path[i];
-                let end = &path[i + 1];
-                if start.x == end.x {
-                    // Vertical section
-                    for y in start.y.min(end.y)..=start.y.max(end.y) {
-                        inner[idx(start.x, y)] = Cell::Rock;
-                    }
-                } else {
-                    // Horizontal section
-                    for x in start.x.min(end.x)..=start.x.max(end.x) {
-                        inner[idx(x, start.y)] = Cell::Rock;
-                    }
-                }
+            for c in polyline(path) {
+                inner[idx(c.x as usize, c.y as usize)] = Cell::Rock;
+                bounds = bounds.including(c);
             }
         }
-        Self { inner }
+        Self { inner, bounds }
     }
 
     /// Keeps dropping sand into the cave until it either falls out
     /// the bottom, or starts blocking the entrance. Returns the
     /// number of sand units dropped.
-    fn drop_sand_until_terminal(&mut self) -> usize {
-        let mut counter = 0;
+    fn drop_sand_until_terminal(&mut self, frames: &mut Frames) -> usize {
+        let mut counter: usize = 0;
         while !self.drop_one_unit_of_sand() {
             counter += 1;
+            if counter.is_multiple_of(FRAME_INTERVAL) {
+                frames.record(|| self.image().scaled(2));
+            }
         }
+        frames.record(|| self.image().scaled(2));
         counter
     }
 
+    fn image(&self) -> Image {
+        Image::from_fn(self.bounds, |c| {
+            match self.inner[idx(c.x as usize, c.y as usize)] {
+                Cell::Empty => Rgb::BLACK,
+                Cell::Rock => Rgb::GREY,
+                Cell::Sand => Rgb::SAND,
+            }
+        })
+    }
+
     /// Drops one sand unit into the cave. Returns true if the sand fell
     /// through into the void below, or came to rest in front of the
     /// inlet.
     fn drop_one_unit_of_sand(&mut self) -> bool {
-        let (mut x, mut y) = (500, 0);
+        let (mut x, mut y) = (SOURCE.x as usize, SOURCE.y as usize);
         loop {
             // Fell through.
             if y >= CAVE_DEPTH - 1 {
@@ -113,6 +148,9 @@ impl Cave {
     }
 }
 
+/// Where the sand pours in.
+const SOURCE: Coordinate = Coordinate::new(500, 0);
+
 /// Returns the cave index for a given x, y coordinate pair.
 fn idx(x: usize, y: usize) -> usize {
     y * 1000 + x
@@ -125,7 +163,7 @@ enum Cell {
     Sand,
 }
 
-type Path = Vec<Point>;
+type Path = Vec<Coordinate>;
 
 fn parse_paths(input: &str) -> Vec<Path> {
     input
@@ -142,16 +180,9 @@ fn parse_path(i: &str) -> IResult<&str, Path> {
     separated_list1(tag(" -> "), parse_point)(i)
 }
 
-#[derive(Debug)]
-struct Point {
-    x: usize,
-    y: usize,
-}
-
-fn parse_point(i: &str) -> IResult<&str, Point> {
-    map(tuple((u16, tag(","), u16)), |(x, _, y)| Point {
-        x: x as usize,
-        y: y as usize,
+fn parse_point(i: &str) -> IResult<&str, Coordinate> {
+    map(tuple((u16, tag(","), u16)), |(x, _, y)| {
+        Coordinate::new(x as isize, y as isize)
     })(i)
 }
 
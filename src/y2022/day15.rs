@@ -1,127 +1,94 @@
-use nom::{bytes::complete::tag, character::complete::i32, combinator::all_consuming, IResult};
+use crate::coordinate::Coordinate;
+use crate::interval::IntervalSet;
+use crate::parsing::{coordinate, lines, parse_all, PResult};
+use nom::{bytes::complete::tag, sequence::preceded};
 use rayon::prelude::*;
 use rustc_hash::FxHashSet;
+use std::ops::Range;
 
 pub fn solve() {
-    let input = include_str!("inputs/15.txt");
-    println!("{}\n{}", part1(input, 2_000_000), part2(input, 4_000_000))
+    let (part1, part2) = run(include_str!("inputs/15.txt"));
+    println!("{part1}\n{part2}")
 }
 
-fn part1(input: &str, row: i32) -> usize {
-    let (sensors, _beacons) = parse_sensors_and_beacons(input);
-    let mut ranges: Vec<_> = sensors.iter().map(|s| s.row_coverage(row)).collect();
-    ranges.sort_by_key(|c| c.0);
-    let mut combined_ranges = vec![];
-    ranges.iter().for_each(|(start, end)| {
-        if let Some((_, last_end)) = combined_ranges.last() {
-            if start <= last_end {
-                combined_ranges.last_mut().unwrap().1 = *end.max(last_end);
-            } else {
-                combined_ranges.push((*start, *end));
-            }
-        } else {
-            combined_ranges.push((*start, *end));
-        }
-    });
-    combined_ranges
+pub fn run(input: &str) -> (String, String) {
+    (
+        part1(input, 2_000_000).to_string(),
+        part2(input, 4_000_000).to_string(),
+    )
+}
+
+fn part1(input: &str, row: isize) -> u64 {
+    let (sensors, beacons) = parse_sensors_and_beacons(input);
+    let covered: IntervalSet = sensors.iter().filter_map(|s| s.row_coverage(row)).collect();
+    let known_beacons = beacons
         .iter()
-        .map(|(start, end)| end.abs_diff(*start) as usize)
-        .sum()
+        .filter(|b| b.y == row && covered.contains(b.x as i64))
+        .count();
+    covered.len() - known_beacons as u64
 }
 
-fn part2(input: &str, limits: i32) -> i64 {
+fn part2(input: &str, limits: isize) -> isize {
     let (sensors, beacons) = parse_sensors_and_beacons(input);
-    let (x, y) = sensors
+    let beacon = sensors
         .par_iter()
         .flat_map(|s| s.just_out_of_reach(limits, &sensors))
         .find_any(|b| !beacons.contains(b))
         .expect("failed to find beacon");
-    x as i64 * 4_000_000 + y as i64
+    beacon.x * 4_000_000 + beacon.y
 }
 
-fn parse_sensors_and_beacons(input: &str) -> (Vec<Sensor>, FxHashSet<(i32, i32)>) {
-    let sensors: Vec<_> = input
-        .lines()
-        .map(|l| {
-            all_consuming(parse_sensor)(l)
-                .expect("failed to parse sensor")
-                .1
-        })
-        .collect();
+fn parse_sensors_and_beacons(input: &str) -> (Vec<Sensor>, FxHashSet<Coordinate>) {
+    let sensors = parse_all(input, lines(parse_sensor));
     let beacons: FxHashSet<_> = sensors.iter().map(|s| s.closest_beacon).collect();
     (sensors, beacons)
 }
 
 #[derive(Debug)]
 struct Sensor {
-    x: i32,
-    y: i32,
-    closest_beacon: (i32, i32),
-    detection_range: i32,
+    position: Coordinate,
+    closest_beacon: Coordinate,
+    detection_range: usize,
 }
 
 impl Sensor {
     /// Returns true if the field at coords is covered by this sensor.
-    fn is_covered(&self, coords: &(i32, i32)) -> bool {
-        manhattan_distance(&(self.x, self.y), coords) <= self.detection_range
+    fn is_covered(&self, coords: Coordinate) -> bool {
+        self.position.manhattan_distance(coords) <= self.detection_range
     }
 
-    /// Returns a pair of lower and upper bound of all x-coordinates
-    /// covered by this sensor which match a given y-coordinate.
-    fn row_coverage(&self, y: i32) -> (i32, i32) {
-        let range = self.detection_range - self.y.abs_diff(y) as i32;
-        let a = self.x - range;
-        let b = self.x + range;
-        (a.min(b), a.max(b))
+    /// Returns the range of all x-coordinates covered by this sensor
+    /// which match a given y-coordinate, if it reaches that far.
+    fn row_coverage(&self, y: isize) -> Option<Range<i64>> {
+        let range = self
+            .detection_range
+            .checked_sub(self.position.y.abs_diff(y))? as i64;
+        let x = self.position.x as i64;
+        Some(x - range..x + range + 1)
     }
 
-    /// Returns the fields that are surrounding the coverage of this
-    /// sensor, i.e. manhattan distance = detection range + 1. For
-    /// performance reasons this only includes fields where 0 <= field
-    /// <= limits.
-    fn just_out_of_reach(&self, limits: i32, sensors: &[Sensor]) -> Vec<(i32, i32)> {
-        let detection_range = self.detection_range + 1;
-        for y_offset in -detection_range..=detection_range {
-            let y = self.y + y_offset;
-            if y < 0 || y > limits {
-                continue;
-            }
-            let x_offset = detection_range - y_offset.abs();
-            let x = self.x - x_offset;
-            if x >= 0 && x <= limits && !sensors.iter().any(|s| s.is_covered(&(x, y))) {
-                return vec![(x, y)];
-            }
-            let x = self.x + x_offset;
-            if x >= 0 && x <= limits && !sensors.iter().any(|s| s.is_covered(&(x, y))) {
-                return vec![(x, y)];
-            }
-        }
-        vec![]
+    /// Returns the first field surrounding the coverage of this
+    /// sensor, i.e. manhattan distance = detection range + 1, which
+    /// is not covered by any sensor. For performance reasons this
+    /// only includes fields where 0 <= field <= limits.
+    fn just_out_of_reach(&self, limits: isize, sensors: &[Sensor]) -> Option<Coordinate> {
+        self.position
+            .at_manhattan_radius(self.detection_range + 1)
+            .filter(|c| (0..=limits).contains(&c.x) && (0..=limits).contains(&c.y))
+            .find(|&c| !sensors.iter().any(|s| s.is_covered(c)))
     }
 }
 
-/// Returns the manhattan distance between two points.
-fn manhattan_distance(left: &(i32, i32), right: &(i32, i32)) -> i32 {
-    (left.0.abs_diff(right.0) + left.1.abs_diff(right.1)) as i32
-}
-
-fn parse_sensor(i: &str) -> IResult<&str, Sensor> {
-    let (i, _) = tag("Sensor at x=")(i)?;
-    let (i, x) = i32(i)?;
-    let (i, _) = tag(", y=")(i)?;
-    let (i, y) = i32(i)?;
-    let (i, _) = tag(": closest beacon is at x=")(i)?;
-    let (i, bx) = i32(i)?;
-    let (i, _) = tag(", y=")(i)?;
-    let (i, by) = i32(i)?;
-    let detection_range = (x.abs_diff(bx) + y.abs_diff(by)) as i32;
+fn parse_sensor(i: &str) -> PResult<'_, Sensor> {
+    let (i, position) = preceded(tag("Sensor at x="), coordinate(tag(", y=")))(i)?;
+    let (i, closest_beacon) =
+        preceded(tag(": closest beacon is at x="), coordinate(tag(", y=")))(i)?;
     Ok((
         i,
         Sensor {
-            x,
-            y,
-            closest_beacon: (bx, by),
-            detection_range,
+            position,
+            closest_beacon,
+            detection_range: position.manhattan_distance(closest_beacon),
         },
     ))
 }
@@ -1,109 +1,134 @@
-use std::collections::VecDeque;
-
+use crate::graph::IndexedGraph;
+use crate::interner::Interner;
+use crate::parsing::{lines, number, parse_all, PResult};
 use itertools::Itertools;
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{alpha1, u16},
-    combinator::{all_consuming, map},
-    multi::separated_list1,
-    IResult,
-};
-use rayon::prelude::*;
-use rustc_hash::{FxHashMap, FxHashSet};
+use nom::{branch::alt, bytes::complete::tag, character::complete::alpha1, multi::separated_list1};
 
 pub fn solve() {
-    let input = include_str!("inputs/16.txt");
-    let (valves, distances) = setup(input);
-    println!(
-        "{}\n{}",
-        part1(&valves, &distances),
-        part2(&valves, &distances)
-    )
+    let (part1, part2) = run(include_str!("inputs/16.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let valves = setup(input);
+    (part1(&valves).to_string(), part2(&valves).to_string())
 }
 
-fn part1(
-    valves: &FxHashMap<String, Valve>,
-    distances: &FxHashMap<(String, String), Time>,
-) -> Pressure {
-    let volcano = Volcano::new(vec![Agent::default()], 30, valves, distances);
+fn part1(valves: &Valves) -> Pressure {
+    let volcano = Volcano::new(vec![Agent::at(valves.start)], 30, valves);
     volcano.release_pressure()
 }
 
-fn part2(
-    valves: &FxHashMap<String, Valve>,
-    distances: &FxHashMap<(String, String), Time>,
-) -> Pressure {
+fn part2(valves: &Valves) -> Pressure {
     let volcano = Volcano::new(
-        vec![Agent::default(), Agent::default()],
+        vec![Agent::at(valves.start), Agent::at(valves.start)],
         26,
         valves,
-        distances,
     );
     volcano.release_pressure()
 }
 
-fn setup(input: &str) -> (FxHashMap<String, Valve>, FxHashMap<(String, String), Time>) {
-    let valves: FxHashMap<_, _> = input
-        .lines()
-        .map(|l| {
-            all_consuming(parse_valve)(l)
-                .expect("failed to parse valve")
-                .1
-        })
-        .map(|v| (v.name.clone(), v))
-        .collect();
-    // These are the valves that can actually be opened, as well as
-    // the starting position. We use this to pre-calculate distances
-    // between useful graph nodes. The useless valves inbetween are
-    // just used to modify edge weights.
-    let mut useful_valves: Vec<Valve> = valves
-        .values()
-        .filter(|&v| v.flow_rate > 0)
-        .cloned()
-        .collect();
-    useful_valves.push(valves.get("AA").unwrap().clone());
-    let distances: FxHashMap<_, _> = useful_valves
+fn setup(input: &str) -> Valves {
+    let parsed = parse_all(input, lines(parse_valve));
+    let mut names: Interner<ValveId> = Interner::new();
+    for valve in &parsed {
+        names.intern(valve.name);
+    }
+    let mut flow_rates = vec![0; names.len()];
+    let mut tunnels = IndexedGraph::new();
+    for id in names.ids() {
+        tunnels.add_node(id);
+    }
+    for valve in &parsed {
+        let from = names.intern(valve.name);
+        flow_rates[from as usize] = valve.flow_rate;
+        for to in &valve.leads_to {
+            tunnels.add_edge(from, names.get(to).expect("unknown valve"), 1);
+        }
+    }
+    // Only valves worth opening get a bit in the `opened` bitset.
+    let mut masks = vec![0; names.len()];
+    let useful = names.ids().filter(|&id| flow_rates[id as usize] > 0);
+    for (bit, id) in useful.enumerate() {
+        assert!(bit < 64, "more than 64 valves with a flow rate");
+        masks[id as usize] = 1 << bit;
+    }
+    // Pre-calculate the time to get to and open each valve, the
+    // valves inbetween are just used to modify edge weights.
+    let etas = tunnels
+        .bfs_distances()
         .iter()
-        .permutations(2)
-        .par_bridge()
-        .map(|vs| {
-            (
-                (vs[0].name.clone(), vs[1].name.clone()),
-                vs[0].eta(&vs[1].name, &valves),
-            )
+        .enumerate()
+        .map(|(from, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(to, hops)| match hops {
+                    Some(hops) if from != to => *hops as Time + 1,
+                    _ => Time::MAX,
+                })
+                .collect()
         })
         .collect();
-    (valves, distances)
+    Valves {
+        flow_rates,
+        masks,
+        etas,
+        start: names.get("AA").expect("no start valve"),
+    }
 }
 
+/// All valves by id, with the time it takes to walk from one to
+/// another and open it.
+#[derive(Debug)]
+struct Valves {
+    flow_rates: Vec<Pressure>,
+    /// The bit of each valve in a bitset of opened valves, zero for
+    /// valves with no flow rate.
+    masks: Vec<u64>,
+    etas: Vec<Vec<Time>>,
+    start: ValveId,
+}
+
+impl Valves {
+    fn ids(&self) -> impl Iterator<Item = ValveId> {
+        0..self.flow_rates.len() as ValveId
+    }
+
+    fn flow_rate(&self, valve: ValveId) -> Pressure {
+        self.flow_rates[valve as usize]
+    }
+
+    fn mask(&self, valve: ValveId) -> u64 {
+        self.masks[valve as usize]
+    }
+
+    fn eta(&self, from: ValveId, to: ValveId) -> Time {
+        self.etas[from as usize][to as usize]
+    }
+}
+
+type ValveId = u8;
 type Time = u8;
 type Pressure = u16;
 
 #[derive(Clone, Debug)]
 struct Volcano<'a> {
-    agents: Vec<Agent<'a>>,
-    opened: FxHashSet<&'a str>,
+    agents: Vec<Agent>,
+    /// Bitset of opened valves, see [`Valves::mask`].
+    opened: u64,
     time_remaining: Time,
     pressure_released: Pressure,
-    valves: &'a FxHashMap<String, Valve>,
-    distances: &'a FxHashMap<(String, String), Time>,
+    valves: &'a Valves,
 }
 
 impl<'a> Volcano<'a> {
-    fn new(
-        agents: Vec<Agent<'a>>,
-        time_remaining: Time,
-        valves: &'a FxHashMap<String, Valve>,
-        distances: &'a FxHashMap<(String, String), Time>,
-    ) -> Self {
+    fn new(agents: Vec<Agent>, time_remaining: Time, valves: &'a Valves) -> Self {
         Self {
             agents,
-            opened: FxHashSet::default(),
+            opened: 0,
             time_remaining,
             pressure_released: 0,
             valves,
-            distances,
         }
     }
 
@@ -122,24 +147,20 @@ impl<'a> Volcano<'a> {
         // found option, just abort right here.
         let potential: u16 = self
             .valves
-            .values()
+            .ids()
             .filter_map(|v| {
-                if self.opened.contains(v.name.as_str()) {
+                if self.is_open(v) {
                     None
                 } else {
                     let best_eta = self
                         .agents
                         .iter()
-                        .map(|agent| {
-                            self.distances
-                                .get(&(agent.position.to_string(), v.name.to_string()))
-                                .unwrap_or(&Time::MAX)
-                        })
+                        .map(|agent| self.valves.eta(agent.position, v))
                         .min()
                         .unwrap();
                     Some(
-                        v.flow_rate
-                            * ((self.time_remaining as u16).saturating_sub(*best_eta as u16)),
+                        self.valves.flow_rate(v)
+                            * ((self.time_remaining as u16).saturating_sub(best_eta as u16)),
                     )
                 }
             })
@@ -179,39 +200,36 @@ impl<'a> Volcano<'a> {
                 // version above.
                 let potential: u16 = self
                     .valves
-                    .values()
+                    .ids()
                     .filter_map(|v| {
-                        if &v.name == candidate || self.opened.contains(v.name.as_str()) {
+                        if v == *candidate || self.is_open(v) {
                             None
                         } else {
-                            let eta_from_candidate = self
-                                .distances
-                                .get(&(candidate.to_string(), v.name.to_string()))
-                                .unwrap_or(&Time::MAX);
+                            let eta_from_candidate = self.valves.eta(*candidate, v);
                             let best_agent_eta = self
                                 .agents
                                 .iter()
                                 .enumerate()
                                 .filter_map(|(i, a)| {
                                     if i != *idx {
-                                        Some(
-                                            self.distances
-                                                .get(&(a.position.to_string(), v.name.to_string()))
-                                                .map(|t| *t + self.time_remaining - a.busy_until)
-                                                .unwrap_or(Time::MAX),
-                                        )
+                                        let eta = self.valves.eta(a.position, v);
+                                        Some(if eta == Time::MAX {
+                                            eta
+                                        } else {
+                                            eta + self.time_remaining - a.busy_until
+                                        })
                                     } else {
                                         None
                                     }
                                 })
                                 .min()
                                 .unwrap_or(Time::MAX);
-                            let best_eta = eta_from_candidate.min(&best_agent_eta);
+                            let best_eta = eta_from_candidate.min(best_agent_eta);
                             Some(
-                                v.flow_rate
+                                self.valves.flow_rate(v)
                                     * ((self.time_remaining as u16)
                                         .saturating_sub(*time_taken as u16)
-                                        .saturating_sub(*best_eta as u16)),
+                                        .saturating_sub(best_eta as u16)),
                             )
                         }
                     })
@@ -222,7 +240,7 @@ impl<'a> Volcano<'a> {
 
                 let mut new_volcano = self.clone();
                 let this_agent = &mut new_volcano.agents[*idx];
-                this_agent.position = candidate;
+                this_agent.position = *candidate;
                 this_agent.busy_until = new_volcano.time_remaining - time_taken;
                 new_volcano.time_remaining = new_volcano
                     .agents
@@ -230,7 +248,7 @@ impl<'a> Volcano<'a> {
                     .map(|a| a.busy_until)
                     .max()
                     .unwrap_or_default();
-                new_volcano.opened.insert(candidate);
+                new_volcano.opened |= self.valves.mask(*candidate);
                 new_volcano.pressure_released += pressure_released;
                 new_volcano.pass_time(acc);
             }
@@ -247,9 +265,9 @@ impl<'a> Volcano<'a> {
                 let mut new_volcano = self.clone();
                 for (agent_idx, (next_valve, time_taken, pressure_released)) in options {
                     let this_agent = &mut new_volcano.agents[*agent_idx];
-                    this_agent.position = next_valve;
+                    this_agent.position = *next_valve;
                     this_agent.busy_until = new_volcano.time_remaining - time_taken;
-                    new_volcano.opened.insert(next_valve);
+                    new_volcano.opened |= self.valves.mask(*next_valve);
                     new_volcano.pressure_released += pressure_released;
                 }
                 new_volcano.time_remaining = new_volcano
@@ -266,21 +284,19 @@ impl<'a> Volcano<'a> {
     /// Returns a Vec of valves that could be opened next, along with
     /// the time that would take, and the pressure that would be
     /// released in the time remaining.
-    fn next_valve_candidates(&self, agent: &Agent) -> Vec<(String, Time, Pressure)> {
+    fn next_valve_candidates(&self, agent: &Agent) -> Vec<(ValveId, Time, Pressure)> {
         self.valves
-            .values()
-            .filter(|v| v.flow_rate > 0)
-            .filter(|v| !self.opened.contains(v.name.as_str()))
+            .ids()
+            .filter(|&v| self.valves.flow_rate(v) > 0)
+            .filter(|&v| !self.is_open(v))
             .filter_map(|v| {
-                let time_required: Time = *self
-                    .distances
-                    .get(&(agent.position.to_string(), v.name.to_string()))
-                    .unwrap();
+                let time_required = self.valves.eta(agent.position, v);
                 if time_required <= self.time_remaining {
                     Some((
-                        v.name.clone(),
+                        v,
                         time_required,
-                        (self.time_remaining - time_required) as Pressure * v.flow_rate,
+                        (self.time_remaining - time_required) as Pressure
+                            * self.valves.flow_rate(v),
                     ))
                 } else {
                     None
@@ -288,61 +304,44 @@ impl<'a> Volcano<'a> {
             })
             .collect()
     }
+
+    fn is_open(&self, valve: ValveId) -> bool {
+        self.opened & self.valves.mask(valve) != 0
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct Agent<'a> {
-    position: &'a str,
+struct Agent {
+    position: ValveId,
     busy_until: Time,
 }
 
-impl Default for Agent<'_> {
-    fn default() -> Self {
+impl Agent {
+    fn at(position: ValveId) -> Self {
         Self {
-            position: "AA",
+            position,
             busy_until: 30,
         }
     }
 }
 
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Valve {
-    name: String,
+struct Valve<'a> {
+    name: &'a str,
     flow_rate: Pressure,
-    leads_to: Vec<String>,
-}
-
-impl Valve {
-    /// Returns the number of minutes it takes to reach and open
-    /// another valve when standing in front of this one.
-    fn eta(&self, to: &str, valves: &FxHashMap<String, Valve>) -> Time {
-        let mut options = VecDeque::default();
-        self.leads_to.iter().for_each(|v| options.push_back((v, 1)));
-        while let Some((name, distance)) = options.pop_front() {
-            if name == to {
-                return distance + 1;
-            }
-            valves
-                .get(name)
-                .unwrap()
-                .leads_to
-                .iter()
-                .for_each(|v| options.push_back((v, distance + 1)));
-        }
-        panic!("Failed to find a path to target valve");
-    }
+    leads_to: Vec<&'a str>,
 }
 
-fn parse_valve(i: &str) -> IResult<&str, Valve> {
+fn parse_valve(i: &str) -> PResult<'_, Valve<'_>> {
     let (i, _) = tag("Valve ")(i)?;
-    let (i, name) = map(alpha1, str::to_string)(i)?;
+    let (i, name) = alpha1(i)?;
     let (i, _) = tag(" has flow rate=")(i)?;
-    let (i, flow_rate) = u16(i)?;
+    let (i, flow_rate) = number(i)?;
     let (i, _) = alt((
         tag("; tunnel leads to valve "),
         tag("; tunnels lead to valves "),
     ))(i)?;
-    let (i, leads_to) = separated_list1(tag(", "), map(alpha1, str::to_string))(i)?;
+    let (i, leads_to) = separated_list1(tag(", "), alpha1)(i)?;
     Ok((
         i,
         Valve {
@@ -371,13 +370,11 @@ Valve JJ has flow rate=21; tunnel leads to valve II";
 
     #[test]
     fn part1_example() {
-        let (v, d) = setup(INPUT);
-        assert_eq!(part1(&v, &d), 1651);
+        assert_eq!(part1(&setup(INPUT)), 1651);
     }
 
     #[test]
     fn part2_example() {
-        let (v, d) = setup(INPUT);
-        assert_eq!(part2(&v, &d), 1707);
+        assert_eq!(part2(&setup(INPUT)), 1707);
     }
 }
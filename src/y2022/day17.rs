@@ -1,89 +1,82 @@
+use crate::coordinate::Bounds;
+use crate::cycle::{extrapolate, find_cycle};
+use crate::replay::Simulation;
+use crate::viz::{Frames, Image, Rgb};
+
 pub fn solve() {
-    let input = include_str!("inputs/17.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/17.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
-    let jets = parse_jets(input);
-    let mut jets = jets.iter().copied().cycle();
-    let mut chamber = Chamber::new(&mut jets);
-    (0..2022).for_each(|_| chamber.spawn_piece());
+    let mut chamber = Chamber::new(parse_jets(input));
+    let mut frames = Frames::new("2022-17-part1");
+    (0..2022).for_each(|_| {
+        chamber.spawn_piece();
+        frames.record(|| chamber.image().scaled(4));
+    });
     chamber.real_height()
 }
 
-fn part2(input: &str) -> u128 {
-    let jets = parse_jets(input);
-    let mut infinite_jets = jets.iter().copied().cycle();
-    let mut chamber = Chamber::new(&mut infinite_jets);
+fn part2(input: &str) -> usize {
+    let mut chamber = Chamber::new(parse_jets(input));
 
     // 1T is way too many iterations. But because inputs are
-    // deterministic, we know it can loop. This records a pattern of 5
-    // heights, which repeats eventually. With that we can extrapolate
-    // total height gain.
-
-    // Run to first clear and record that height.
-    chamber.spawn_piece();
-    let mut base_iterations = 1;
-    while chamber.height() != 0 {
-        chamber.spawn_piece();
-        base_iterations += 1;
-    }
-    let base_height = chamber.real_height();
-
-    // Record a cycle pattern of pieces to clear.
-    let mut repetition_pattern: Vec<usize> = vec![];
-    let mut loop_iterations = 0u128;
-    for i in 0.. {
-        if i > 0 && chamber.height() == 0 {
-            repetition_pattern.push(i - repetition_pattern.iter().sum::<usize>());
-            // NB My input only clears rows twice per cycle, so I can
-            // use a very short pattern, other inputs might require
-            // larger patterns.
-            if repetition_pattern.len() == 2 {
-                break;
-            }
-        }
-        loop_iterations += 1;
-        chamber.spawn_piece();
-    }
+    // deterministic, we know it will loop eventually, and can
+    // extrapolate the total height gain from a single loop.
+    let mut heights = vec![0];
+    let cycle = find_cycle(
+        &mut chamber,
+        |chamber| {
+            chamber.spawn_piece();
+            heights.push(chamber.real_height());
+        },
+        Chamber::state,
+    );
+    extrapolate(&heights, cycle, 1_000_000_000_000)
+}
 
-    // Try to find that pattern again.
-    let mut pattern_matches = 0;
-    let mut last_clear = 0;
-    for i in 0.. {
-        if i > 0 && chamber.height() == 0 {
-            if repetition_pattern[pattern_matches] == i - last_clear {
-                pattern_matches += 1;
-            } else {
-                pattern_matches = 0;
-            }
-            if pattern_matches == repetition_pattern.len() - 1 {
-                break;
-            }
-            last_clear = i;
-        }
-        loop_iterations += 1;
-        chamber.spawn_piece();
-    }
+/// Drops the pieces of part 1 one at a time.
+pub fn replay() -> Box<dyn Simulation> {
+    Box::new(Replay {
+        chamber: Chamber::new(parse_jets(include_str!("inputs/17.txt"))),
+        pieces: 0,
+    })
+}
 
-    let post_loop_height = chamber.real_height();
-    let loop_gain = post_loop_height - base_height;
+struct Replay {
+    chamber: Chamber,
+    pieces: usize,
+}
 
-    // Finish up the last bit to get to 1T iterations.
-    let loops = (1_000_000_000_000u128 - base_iterations) / loop_iterations;
-    let extra_iterations = (1_000_000_000_000u128 - base_iterations) % loop_iterations;
-    for _ in 0..extra_iterations {
-        chamber.spawn_piece();
+impl Simulation for Replay {
+    fn step(&mut self) -> bool {
+        self.pieces < 2022 && {
+            self.chamber.spawn_piece();
+            self.pieces += 1;
+            true
+        }
     }
 
-    let extra_height = chamber.real_height() - post_loop_height;
-
-    base_height as u128 + loops * loop_gain as u128 + extra_height as u128
+    fn image(&self) -> Image {
+        self.chamber.image()
+    }
 }
 
-struct Chamber<'a> {
+/// How many rows from the top of the tower are considered when
+/// looking for a repeating state. Falling pieces practically never
+/// reach further down than this.
+const STATE_DEPTH: usize = 64;
+
+struct Chamber {
     source: TetrisPieceSource,
-    jets: &'a mut dyn Iterator<Item = Jet>,
+    jets: Vec<Jet>,
+    /// The index of the next jet to apply.
+    next_jet: usize,
     /// Each row is the lower 7 bits, where a set bit is a piece. The
     /// first row is the bottom-most.
     inner: Vec<u8>,
@@ -93,11 +86,12 @@ struct Chamber<'a> {
     unreachable: usize,
 }
 
-impl<'a> Chamber<'a> {
-    fn new(jets: &'a mut impl Iterator<Item = Jet>) -> Self {
+impl Chamber {
+    fn new(jets: Vec<Jet>) -> Self {
         Self {
             source: TetrisPieceSource::default(),
             jets,
+            next_jet: 0,
             inner: Vec::default(),
             unreachable: 0,
         }
@@ -109,12 +103,33 @@ impl<'a> Chamber<'a> {
         self.unreachable + self.height()
     }
 
+    /// Returns everything that determines how the following pieces
+    /// fall: the last piece, the next jet, and the top of the tower.
+    fn state(&self) -> (TetrisPiece, usize, Vec<u8>) {
+        let height = self.height();
+        let top = self.inner[height.saturating_sub(STATE_DEPTH)..height].to_vec();
+        (self.source.last, self.next_jet, top)
+    }
+
     /// Returns the height of the tower built, excluding unreachable
     /// rows. Useful for indexing into the inner Vec.
     fn height(&self) -> usize {
         self.inner.len() - self.inner.iter().rev().take_while(|&r| *r == 0).count()
     }
 
+    /// Draws the top of the tower, as deep as is considered for the state.
+    fn image(&self) -> Image {
+        let height = self.height();
+        Image::from_fn(Bounds::from_size(7, STATE_DEPTH), |c| {
+            let row = height.checked_sub(c.y as usize + 1);
+            match row.map(|row| self.inner[row] & (0b1000000 >> c.x)) {
+                None => Rgb::GREY,
+                Some(0) => Rgb::BLACK,
+                Some(_) => Rgb::BLUE,
+            }
+        })
+    }
+
     /// Spans the next piece and simulates it falling until it comes
     /// to rest somewhere, modifying the internal state.
     fn spawn_piece(&mut self) {
@@ -130,7 +145,9 @@ impl<'a> Chamber<'a> {
         let mut dropped = 0;
         loop {
             // Apply the jet.
-            let proposed_x = match self.jets.next().unwrap() {
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+            let proposed_x = match jet {
                 Jet::Left => x.saturating_sub(1),
                 Jet::Right => (7 - piece.width()).min(x + 1),
             };
@@ -180,7 +197,7 @@ fn parse_jets(s: &str) -> Vec<Jet> {
         .collect()
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 enum TetrisPiece {
     HorizontalBar,
     Plus,
@@ -271,4 +288,9 @@ mod tests {
     fn part1_example() {
         assert_eq!(part1(INPUT), 3068);
     }
+
+    #[test]
+    fn part2_example() {
+        assert_eq!(part2(INPUT), 1514285714288);
+    }
 }
@@ -1,4 +1,5 @@
-use std::collections::VecDeque;
+use crate::coordinate::{Bounds3, Coordinate3};
+use crate::unionfind::graph_components;
 
 use nom::{
     bytes::complete::tag,
@@ -10,131 +11,91 @@ use nom::{
 use rustc_hash::FxHashSet;
 
 pub fn solve() {
-    let input = include_str!("inputs/18.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/18.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
-    let rocks: FxHashSet<Rock> = input
-        .lines()
-        .map(|l| {
-            all_consuming(parse_rock)(l)
-                .expect("failed to parse rock")
-                .1
-        })
-        .collect();
+    let rocks = parse_rocks(input);
 
     // Get all rock-neighbouring fields that aren't rocks themselves,
     // thus sides of a rock that are touching the air.
     rocks
         .iter()
-        .map(|[x, y, z]| {
-            [
-                [(*x + 1), *y, *z],
-                [(*x - 1), *y, *z],
-                [*x, (*y + 1), *z],
-                [*x, (*y - 1), *z],
-                [*x, *y, (*z + 1)],
-                [*x, *y, (*z - 1)],
-            ]
-            .iter()
-            .copied()
-            .filter(|coords| !rocks.contains(coords))
-            .count()
+        .map(|rock| {
+            rock.neighbours()
+                .iter()
+                .filter(|coords| !rocks.contains(coords))
+                .count()
         })
         .sum()
 }
 
 fn part2(input: &str) -> usize {
-    let rocks: FxHashSet<Rock> = input
-        .lines()
-        .map(|l| {
-            all_consuming(parse_rock)(l)
-                .expect("failed to parse rock")
-                .1
-        })
-        .collect();
-
-    // Find the bounds of the 3D shape described.
-    let min_x = rocks.iter().map(|[x, _, _]| x).min().unwrap();
-    let min_y = rocks.iter().map(|[_, y, _]| y).min().unwrap();
-    let min_z = rocks.iter().map(|[_, _, z]| z).min().unwrap();
-    let max_x = rocks.iter().map(|[x, _, _]| x).max().unwrap();
-    let max_y = rocks.iter().map(|[_, y, _]| y).max().unwrap();
-    let max_z = rocks.iter().map(|[_, _, z]| z).max().unwrap();
-
-    // Collect all empty (non-rock) fields in the box surrounding the
-    // shape.
-    let empty_fields: FxHashSet<Rock> = (min_x - 1..=max_x + 1)
-        .flat_map(|x| {
-            (min_y - 1..=max_y + 1)
-                .flat_map(|y| {
-                    (min_z - 1..=max_z + 1)
-                        .map(|z| [x, y, z])
-                        .filter(|r| !rocks.contains(r))
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>()
-        })
+    let rocks = parse_rocks(input);
+
+    // Find the box surrounding the 3D shape described, leaving a gap
+    // around it.
+    let bounds = Bounds3::enclosing(rocks.iter().copied())
+        .expect("no rocks")
+        .expand(1);
+
+    // Find all the empty fields that are reachable by water, i.e.
+    // connected to the outside.
+    let outside: FxHashSet<Rock> = air_pockets(bounds, &rocks)
+        .into_iter()
+        .find(|pocket| pocket.contains(&bounds.min))
+        .expect("no air outside")
+        .into_iter()
         .collect();
 
-    // Flood fill from the outside to find all the empty fields that
-    // are reachable by water.
-    let outside = flood_fill([*min_x, *min_y, *min_z], &empty_fields);
-
     // Same as part 1, but limited to those reachable fields.
     rocks
         .iter()
-        .map(|[x, y, z]| {
-            [
-                [(*x + 1), *y, *z],
-                [(*x - 1), *y, *z],
-                [*x, (*y + 1), *z],
-                [*x, (*y - 1), *z],
-                [*x, *y, (*z + 1)],
-                [*x, *y, (*z - 1)],
-            ]
-            .iter()
-            .copied()
-            .filter(|coords| outside.contains(coords))
-            .count()
+        .map(|rock| {
+            rock.neighbours()
+                .iter()
+                .filter(|coords| outside.contains(coords))
+                .count()
         })
         .sum()
 }
 
-type Rock = [isize; 3];
+type Rock = Coordinate3;
+
+fn parse_rocks(input: &str) -> FxHashSet<Rock> {
+    input
+        .lines()
+        .map(|l| {
+            all_consuming(parse_rock)(l)
+                .expect("failed to parse rock")
+                .1
+        })
+        .collect()
+}
 
 fn parse_rock(i: &str) -> IResult<&str, Rock> {
     map(separated_list1(tag(","), i32), |coords| {
-        [coords[0] as isize, coords[1] as isize, coords[2] as isize]
+        Coordinate3::new([coords[0] as isize, coords[1] as isize, coords[2] as isize])
     })(i)
 }
 
-/// 3D flood-fill from the start, using only coordinates that are in
-/// valid for traversal, returning the set of visited fields.
-fn flood_fill(start: Rock, valid: &FxHashSet<Rock>) -> FxHashSet<Rock> {
-    let mut visited = FxHashSet::default();
-    let mut queue = VecDeque::from([start]);
-    while let Some([x, y, z]) = queue.pop_front() {
-        let neighbours = [
-            [(x + 1), y, z],
-            [(x - 1), y, z],
-            [x, (y + 1), z],
-            [x, (y - 1), z],
-            [x, y, (z + 1)],
-            [x, y, (z - 1)],
-        ];
-        for n in &neighbours {
-            if visited.contains(n) {
-                continue;
-            }
-            if valid.contains(n) {
-                visited.insert(*n);
-                queue.push_back(*n);
-            }
-        }
-    }
-    visited
+/// Returns the connected groups of fields within bounds which aren't
+/// rocks.
+fn air_pockets(bounds: Bounds3, rocks: &FxHashSet<Rock>) -> Vec<Vec<Rock>> {
+    let air = || bounds.coordinates().filter(|field| !rocks.contains(field));
+    let edges = air().flat_map(|field| {
+        field
+            .neighbours_within(bounds)
+            .into_iter()
+            .filter(|n| !rocks.contains(n))
+            .map(move |n| (field, n))
+    });
+    graph_components(air(), edges)
 }
 
 #[cfg(test)]
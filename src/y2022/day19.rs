@@ -1,36 +1,27 @@
-use nom::{bytes::complete::tag, character::complete::u8, combinator::all_consuming, IResult};
+use crate::parsing::{lines, number, parse_all, PResult};
+use nom::bytes::complete::tag;
 
 pub fn solve() {
-    let input = include_str!("inputs/19.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/19.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> u32 {
-    let blueprints: Vec<Blueprint> = input
-        .lines()
-        .map(|l| {
-            all_consuming(parse_blueprint)(l)
-                .expect("failed to parse blueprint")
-                .1
-        })
-        .collect();
+    let blueprints = parse_all(input, lines(parse_blueprint));
 
     blueprints.iter().map(Blueprint::quality_level).sum()
 }
 
 fn part2(input: &str) -> u32 {
-    let blueprints: Vec<Blueprint> = input
-        .lines()
-        .take(3)
-        .map(|l| {
-            all_consuming(parse_blueprint)(l)
-                .expect("failed to parse blueprint")
-                .1
-        })
-        .collect();
+    let blueprints = parse_all(input, lines(parse_blueprint));
 
     blueprints
         .iter()
+        .take(3)
         .map(|bp| bp.max_geodes(32) as u32)
         .product()
 }
@@ -137,7 +128,7 @@ impl Blueprint {
             } else if target <= current {
                 1
             } else {
-                div_ceil(target.saturating_sub(current), production) + 1
+                target.saturating_sub(current).div_ceil(production) + 1
             }
         };
 
@@ -255,28 +246,21 @@ impl Blueprint {
     }
 }
 
-/// Divides and rounds up. Actual div_ceil is still unstable, and I
-/// don't want to use a nightly version, so here's a specialised port.
-fn div_ceil(lhs: u8, rhs: u8) -> u8 {
-    assert!(rhs != 0);
-    lhs / rhs + (lhs % rhs).min(1)
-}
-
-fn parse_blueprint(i: &str) -> IResult<&str, Blueprint> {
+fn parse_blueprint(i: &str) -> PResult<'_, Blueprint> {
     let (i, _) = tag("Blueprint ")(i)?;
-    let (i, id) = u8(i)?;
+    let (i, id) = number(i)?;
     let (i, _) = tag(": Each ore robot costs ")(i)?;
-    let (i, ore_robot_cost) = u8(i)?;
+    let (i, ore_robot_cost) = number(i)?;
     let (i, _) = tag(" ore. Each clay robot costs ")(i)?;
-    let (i, clay_robot_cost) = u8(i)?;
+    let (i, clay_robot_cost) = number(i)?;
     let (i, _) = tag(" ore. Each obsidian robot costs ")(i)?;
-    let (i, obsidian_robot_ore_cost) = u8(i)?;
+    let (i, obsidian_robot_ore_cost) = number(i)?;
     let (i, _) = tag(" ore and ")(i)?;
-    let (i, obsidian_robot_clay_cost) = u8(i)?;
+    let (i, obsidian_robot_clay_cost) = number(i)?;
     let (i, _) = tag(" clay. Each geode robot costs ")(i)?;
-    let (i, geode_robot_ore_cost) = u8(i)?;
+    let (i, geode_robot_ore_cost) = number(i)?;
     let (i, _) = tag(" ore and ")(i)?;
-    let (i, geode_robot_obsidian_cost) = u8(i)?;
+    let (i, geode_robot_obsidian_cost) = number(i)?;
     let (i, _) = tag(" obsidian.")(i)?;
     Ok((
         i,
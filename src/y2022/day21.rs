@@ -9,8 +9,12 @@ use nom::{
 use rustc_hash::FxHashMap;
 
 pub fn solve() {
-    let input = include_str!("inputs/21.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/21.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> u64 {
@@ -1,186 +1,148 @@
-use itertools::Itertools;
-use rustc_hash::{FxHashMap, FxHashSet};
+use crate::coordinate::{Coordinate, DIAGONAL_DIRECTIONS};
+use crate::replay::Simulation;
+use crate::sparsegrid::SparseGrid;
+use crate::viz::{Image, Rgb};
+use rustc_hash::FxHashMap;
 
 pub fn solve() {
-    let input = include_str!("inputs/23.txt");
-    println!("{}\n{}", part1(input), part2(input))
+    let (part1, part2) = run(include_str!("inputs/23.txt"));
+    println!("{part1}\n{part2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), part2(input).to_string())
 }
 
 fn part1(input: &str) -> usize {
     let mut elves = parse_elves(input);
-
-    // Three relative coordinate pairs to check, and a relative
-    // coordinate pair to propose if that check succeeds.
-    let mut preferences = [
-        // North
-        ([(-1, -1), (0, -1), (1, -1)], (0, -1)),
-        // South
-        ([(-1, 1), (0, 1), (1, 1)], (0, 1)),
-        // West
-        ([(-1, -1), (-1, 0), (-1, 1)], (-1, 0)),
-        // East
-        ([(1, -1), (1, 0), (1, 1)], (1, 0)),
-    ];
-
+    let mut preferences = PREFERENCES;
     for _ in 0..10 {
-        // Tracks how many elves propose to go to a given tile.
-        let mut proposal_counts: FxHashMap<Tile, usize> = FxHashMap::default();
-        // Create a hash set of elf positions for faster checking of
-        // neighbours.
-        let taken: FxHashSet<&Elf> = elves.iter().collect();
-        // Gather proposals.
-        let proposals: Vec<Option<Tile>> = elves
-            .iter()
-            .map(|e| propose_move(e, &preferences, &taken, &mut proposal_counts))
-            .collect();
-        // Execute proposed moves.
-        elves
-            .iter_mut()
-            .zip(proposals)
-            .for_each(|(elf, proposal)| execute_move(elf, proposal, &proposal_counts));
+        spread_out(&mut elves, &preferences);
         // Rotate movement direction preferences.
         preferences.rotate_left(1);
     }
-
-    let (box_width, box_height) = bounding_box(&elves);
-    box_width * box_height - elves.len()
+    elves.empty_cells()
 }
 
 fn part2(input: &str) -> usize {
     let mut elves = parse_elves(input);
-
-    // Three relative coordinate pairs to check, and a relative
-    // coordinate pair to propose if that check succeeds.
-    let mut preferences = [
-        // North
-        ([(-1, -1), (0, -1), (1, -1)], (0, -1)),
-        // South
-        ([(-1, 1), (0, 1), (1, 1)], (0, 1)),
-        // West
-        ([(-1, -1), (-1, 0), (-1, 1)], (-1, 0)),
-        // East
-        ([(1, -1), (1, 0), (1, 1)], (1, 0)),
-    ];
-
-    let mut i = 0;
-
-    loop {
-        i += 1;
-        // Tracks how many elves propose to go to a given tile.
-        let mut proposal_counts: FxHashMap<Tile, usize> = FxHashMap::default();
-        // Create a hash set of elf positions for faster checking of
-        // neighbours.
-        let taken: FxHashSet<&Elf> = elves.iter().collect();
-        // Gather proposals.
-        let proposals: Vec<Option<Tile>> = elves
-            .iter()
-            .map(|e| propose_move(e, &preferences, &taken, &mut proposal_counts))
-            .collect();
-        if proposals.iter().all(Option::is_none) {
-            break;
-        }
-        // Execute proposed moves.
-        elves
-            .iter_mut()
-            .zip(proposals)
-            .for_each(|(elf, proposal)| execute_move(elf, proposal, &proposal_counts));
-        // Rotate movement direction preferences.
+    let mut preferences = PREFERENCES;
+    let mut i = 1;
+    while spread_out(&mut elves, &preferences) {
         preferences.rotate_left(1);
+        i += 1;
     }
-
     i
 }
 
-fn parse_elves(input: &str) -> Vec<Elf> {
-    input
-        .lines()
-        .enumerate()
-        .flat_map(|(linum, line)| {
-            line.chars()
-                .enumerate()
-                .filter_map(|(colnum, c)| {
-                    if c == '#' {
-                        Some((colnum as isize, linum as isize))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect()
+/// Spreads the elves out one round at a time, until none of them move.
+pub fn replay() -> Box<dyn Simulation> {
+    Box::new(Replay {
+        elves: parse_elves(include_str!("inputs/23.txt")),
+        preferences: PREFERENCES,
+    })
 }
 
-/// (x, y) coordinate pair, where x is right, and y is down. The
-/// origin happens to be at the top left corner of the input, but is
-/// irrelevant because we use signed coordinates.
-type Elf = (isize, isize);
-type Tile = (isize, isize);
-
-/// Execute a proposed move for this elf, if this is the only elf that
-/// proposes to make this move, otherwise do nothing.
-fn execute_move(elf: &mut Elf, proposal: Option<Tile>, counts: &FxHashMap<Tile, usize>) {
-    if let Some(p) = proposal {
-        if *counts.get(&p).unwrap() == 1 {
-            *elf = p;
-        }
-    }
+struct Replay {
+    elves: SparseGrid<()>,
+    preferences: [([Coordinate; 3], Coordinate); 4],
 }
 
-/// Propose a move for this elf, and also record it in counts. Can
-/// propose nothing if there are no elves around, or there are no good
-/// options.
-fn propose_move(
-    elf: &Elf,
-    preferences: &[([Tile; 3], Tile)],
-    elves: &FxHashSet<&Elf>,
-    counts: &mut FxHashMap<Tile, usize>,
-) -> Option<Tile> {
-    let tile_free = |&(x, y)| !elves.contains(&(elf.0 + x, elf.1 + y));
-    let mut alone = true;
-    for neighbour in &[
-        (-1, -1),
-        (0, -1),
-        (1, -1),
-        (-1, 0),
-        (1, 0),
-        (-1, 1),
-        (0, 1),
-        (1, 1),
-    ] {
-        if !tile_free(neighbour) {
-            alone = false;
-            break;
-        }
+impl Simulation for Replay {
+    fn step(&mut self) -> bool {
+        let moved = spread_out(&mut self.elves, &self.preferences);
+        self.preferences.rotate_left(1);
+        moved
     }
-    if alone {
-        return None;
+
+    fn image(&self) -> Image {
+        Image::from_sparse(&self.elves, |elf| match elf {
+            Some(()) => Rgb::GREEN,
+            None => Rgb::BLACK,
+        })
     }
+}
 
-    let mut proposal = None;
+fn parse_elves(input: &str) -> SparseGrid<()> {
+    SparseGrid::parse(input, |c| (c == '#').then_some(()))
+}
 
-    for ([a, b, c], (x, y)) in preferences {
-        if tile_free(a) && tile_free(b) && tile_free(c) {
-            proposal = Some((elf.0 + x, elf.1 + y));
-            break;
+/// Three relative tiles to check, and the relative tile to propose if they're all free, for
+/// north, south, west and east.
+const PREFERENCES: [([Coordinate; 3], Coordinate); 4] = [
+    (
+        [
+            Coordinate::new(-1, -1),
+            Coordinate::new(0, -1),
+            Coordinate::new(1, -1),
+        ],
+        Coordinate::new(0, -1),
+    ),
+    (
+        [
+            Coordinate::new(-1, 1),
+            Coordinate::new(0, 1),
+            Coordinate::new(1, 1),
+        ],
+        Coordinate::new(0, 1),
+    ),
+    (
+        [
+            Coordinate::new(-1, -1),
+            Coordinate::new(-1, 0),
+            Coordinate::new(-1, 1),
+        ],
+        Coordinate::new(-1, 0),
+    ),
+    (
+        [
+            Coordinate::new(1, -1),
+            Coordinate::new(1, 0),
+            Coordinate::new(1, 1),
+        ],
+        Coordinate::new(1, 0),
+    ),
+];
+
+/// Runs one round of proposals and moves, returning `true` if any elf moved.
+fn spread_out(elves: &mut SparseGrid<()>, preferences: &[([Coordinate; 3], Coordinate)]) -> bool {
+    // Tracks how many elves propose to go to a given tile.
+    let mut proposal_counts: FxHashMap<Coordinate, usize> = FxHashMap::default();
+    let proposals: Vec<(Coordinate, Coordinate)> = elves
+        .coordinates()
+        .filter_map(|elf| {
+            let proposal = propose_move(elf, preferences, elves)?;
+            *proposal_counts.entry(proposal).or_default() += 1;
+            Some((elf, proposal))
+        })
+        .collect();
+    // Only move elves which are the only ones proposing their tile.
+    let mut moved = false;
+    for (elf, proposal) in proposals {
+        if proposal_counts[&proposal] == 1 {
+            elves.remove(elf);
+            elves.insert(proposal, ());
+            moved = true;
         }
     }
-
-    if let Some(prop) = proposal {
-        if let Some(p) = counts.get_mut(&prop) {
-            *p += 1;
-        } else {
-            counts.insert(prop, 1);
-        };
-    };
-    proposal
+    moved
 }
 
-/// Returns the size of the smallest rectangle that contains the
-/// elves.
-fn bounding_box(elves: &[Elf]) -> (usize, usize) {
-    let (min_x, max_x) = elves.iter().map(|(x, _)| x).minmax().into_option().unwrap();
-    let (min_y, max_y) = elves.iter().map(|(_, y)| y).minmax().into_option().unwrap();
-    (1 + min_x.abs_diff(*max_x), 1 + min_y.abs_diff(*max_y))
+/// Propose a move for this elf. Can propose nothing if there are no
+/// elves around, or there are no good options.
+fn propose_move(
+    elf: Coordinate,
+    preferences: &[([Coordinate; 3], Coordinate)],
+    elves: &SparseGrid<()>,
+) -> Option<Coordinate> {
+    let tile_free = |offset: &Coordinate| !elves.contains(elf + *offset);
+    if DIAGONAL_DIRECTIONS.iter().all(tile_free) {
+        return None;
+    }
+    preferences
+        .iter()
+        .find(|(checks, _)| checks.iter().all(tile_free))
+        .map(|(_, step)| elf + *step)
 }
 
 #[cfg(test)]
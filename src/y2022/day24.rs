@@ -1,255 +1,140 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
-
-use rustc_hash::FxHashSet;
+use crate::bitgrid::BitGrid;
+use crate::coordinate::{Coordinate, DIRECTIONS};
+use crate::replay::Simulation;
+use crate::viz::{Image, Rgb};
+use itertools::Itertools;
 
 pub fn solve() {
-    let input = include_str!("inputs/24.txt");
-    let (part1, part2) = both_parts(input);
+    let (part1, part2) = run(include_str!("inputs/24.txt"));
     println!("{part1}\n{part2}")
 }
 
-fn both_parts(input: &str) -> (usize, usize) {
-    let (height, width, start_x, end_x, blizzard_positions) = setup(input);
-    let mut trip = 0;
-
-    // Leg 1
-    let mut visited: FxHashSet<(usize, (usize, isize))> = FxHashSet::default();
-    let mut queue: BinaryHeap<State> = BinaryHeap::from([State {
-        x: start_x,
-        y: -1,
-        time: trip,
-        reverse: false,
-    }]);
-    while let Some(state) = queue.pop() {
-        if let Some(distance) = state.step(
-            height,
-            width,
-            start_x,
-            end_x,
-            &mut queue,
-            &mut visited,
-            &blizzard_positions,
-        ) {
-            trip = distance;
-            break;
-        }
-    }
-
-    let part1 = trip;
-
-    // Leg 2 - back
-    let mut visited: FxHashSet<(usize, (usize, isize))> = FxHashSet::default();
-    let mut queue: BinaryHeap<State> = BinaryHeap::from([State {
-        x: end_x,
-        y: height as isize,
-        time: trip,
-        reverse: true,
-    }]);
-    while let Some(state) = queue.pop() {
-        if let Some(distance) = state.step(
-            height,
-            width,
-            start_x,
-            end_x,
-            &mut queue,
-            &mut visited,
-            &blizzard_positions,
-        ) {
-            trip = distance;
-            break;
-        }
-    }
-
-    // Leg 3 - there again
-    let mut visited: FxHashSet<(usize, (usize, isize))> = FxHashSet::default();
-    let mut queue: BinaryHeap<State> = BinaryHeap::from([State {
-        x: start_x,
-        y: -1,
-        time: trip,
-        reverse: false,
-    }]);
-    while let Some(state) = queue.pop() {
-        if let Some(distance) = state.step(
-            height,
-            width,
-            start_x,
-            end_x,
-            &mut queue,
-            &mut visited,
-            &blizzard_positions,
-        ) {
-            trip = distance;
-            break;
-        }
-    }
-
-    (part1, trip)
+pub fn run(input: &str) -> (String, String) {
+    let (part1, part2) = both_parts(input);
+    (part1.to_string(), part2.to_string())
 }
 
-/// Parses the input and returns:
-/// - the height and width of the usable map
-/// - the x-coordinate of the start and end locations
-/// - a Vec of bitmasks for each cell, discribing if there will be a storm
-fn setup(input: &str) -> (usize, usize, usize, usize, Vec<[u128; 8]>) {
-    let height = input.lines().count() - 2;
-    let width = input.lines().next().unwrap().chars().count() - 2;
-    let start_x = input.chars().skip(1).position(|c| c != '#').unwrap();
-    let end_x = width - input.chars().rev().skip(1).position(|c| c != '#').unwrap();
-    let blizzards: Vec<Blizzard> = input
-        .lines()
-        .skip(1)
-        .take(height)
-        .enumerate()
-        .flat_map(|(y, line)| {
-            line.chars()
-                .skip(1)
-                .take(width)
-                .enumerate()
-                .filter_map(|(x, c)| {
-                    if c == '.' {
-                        return None;
-                    }
-                    let heading = match c {
-                        '>' => Heading::Right,
-                        '<' => Heading::Left,
-                        '^' => Heading::Up,
-                        'v' => Heading::Down,
-                        _ => panic!("invalid tile"),
-                    };
-                    Some(Blizzard { x, y, heading })
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect();
-    let mut blizzard_positions: Vec<[u128; 8]> = (0..height * width)
-        .map(|_| [0, 0, 0, 0, 0, 0, 0, 0])
-        .collect();
-    for blizzard in blizzards {
-        (0..128 * 8).for_each(|time| {
-            let (x, y) = blizzard.position_at(time, height, width);
-            let idx = y * width + x;
-            let bitmask = time / 128;
-            let offset = time % 128;
-            blizzard_positions[idx][bitmask] |= 1 << offset;
-        });
-    }
-    (height, width, start_x, end_x, blizzard_positions)
+fn both_parts(input: &str) -> (usize, usize) {
+    let mut valley = Valley::parse(input);
+    let there = valley.cross(valley.entrance, valley.exit);
+    let back = valley.cross(valley.exit, valley.entrance);
+    let there_again = valley.cross(valley.entrance, valley.exit);
+    (there, there + back + there_again)
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-struct State {
-    x: usize,
-    y: isize,
-    time: usize,
-    reverse: bool,
+/// Crosses the valley there, back and there again, one minute at a time.
+pub fn replay() -> Box<dyn Simulation> {
+    let valley = Valley::parse(include_str!("inputs/24.txt"));
+    let bounds = valley.blizzards[0].bounds();
+    Box::new(Replay {
+        legs: vec![
+            (valley.entrance, valley.exit),
+            (valley.exit, valley.entrance),
+            (valley.entrance, valley.exit),
+        ],
+        expedition: BitGrid::new(bounds.width(), bounds.height()),
+        valley,
+    })
 }
 
-impl State {
-    #[allow(clippy::too_many_arguments)]
-    /// Takes a step during BFS pathfinding, returns `Some(distance)`
-    /// if it reaches the goal.
-    fn step(
-        &self,
-        height: usize,
-        width: usize,
-        start_x: usize,
-        end_x: usize,
-        queue: &mut BinaryHeap<Self>,
-        visited: &mut FxHashSet<(usize, (usize, isize))>,
-        blizzard_positions: &[[u128; 8]],
-    ) -> Option<usize> {
-        if (!self.reverse && self.x == end_x && self.y == height as isize - 1)
-            || (self.reverse && self.x == start_x && self.y == 0)
-        {
-            return Some(self.time);
-        }
+struct Replay {
+    valley: Valley,
+    /// The crossings still to make, from and to.
+    legs: Vec<(Coordinate, Coordinate)>,
+    expedition: BitGrid,
+}
 
-        if visited.contains(&(self.time, (self.x, self.y))) {
-            return None;
+impl Simulation for Replay {
+    fn step(&mut self) -> bool {
+        let Some(&(from, to)) = self.legs.first() else {
+            return false;
+        };
+        if self.expedition.get(to) {
+            // Step out of the valley.
+            self.valley.blow();
+            self.expedition = BitGrid::new(self.expedition.width(), self.expedition.height());
+            self.legs.remove(0);
         } else {
-            visited.insert((self.time, (self.x, self.y)));
+            self.expedition = self.valley.spread(&self.expedition, from);
         }
+        true
+    }
 
-        [
-            (self.x, (self.y + 1).min(height as isize - 1)),
-            ((self.x + 1).min(width - 1), self.y),
-            (self.x, self.y),
-            (self.x, (self.y - 1).max(0)),
-            (self.x.saturating_sub(1), self.y),
-        ]
-        .iter()
-        .copied()
-        .filter(|(x, y)| {
-            // Allow waiting outside the grid in the starting position.
-            (!self.reverse && (*y >= 0 || *x == start_x))
-                || (self.reverse && (*y < height as isize || *x == end_x))
-        })
-        .filter(|(x, y)| {
-            (*y < 0 && !self.reverse)
-                || (*y == height as isize && self.reverse)
-                || 0 == (blizzard_positions[*y as usize * width + x][self.time / 128]
-                    & 1 << (self.time % 128))
+    fn image(&self) -> Image {
+        Image::from_fn(self.expedition.bounds(), |c| {
+            let blizzards = self.valley.blizzards.iter().filter(|b| b.get(c)).count() as u8;
+            if self.expedition.get(c) {
+                Rgb::GREEN
+            } else {
+                Rgb(0, 0, blizzards * 60)
+            }
         })
-        .for_each(|opt| {
-            let mut new_self = *self;
-            new_self.time += 1;
-            (new_self.x, new_self.y) = opt;
-            queue.push(new_self);
-        });
-        None
     }
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+/// The valley within the walls, with its blizzards as they are at the current minute.
+struct Valley {
+    /// The blizzards blowing in each of `DIRECTIONS`.
+    blizzards: [BitGrid; 4],
+    /// The cells next to the entrance and the exit.
+    entrance: Coordinate,
+    exit: Coordinate,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        if self.reverse {
-            other
-                .time
-                .cmp(&self.time)
-                .then_with(|| (other.x as isize + other.y).cmp(&(self.x as isize + self.y)))
-        } else {
-            other
-                .time
-                .cmp(&self.time)
-                .then_with(|| (self.x as isize + self.y).cmp(&(other.x as isize + other.y)))
+impl Valley {
+    fn parse(input: &str) -> Self {
+        let lines = input.lines().map(str::trim_end).collect_vec();
+        let (first, last) = (lines[0], lines[lines.len() - 1]);
+        let inside = lines[1..lines.len() - 1]
+            .iter()
+            .map(|line| &line[1..line.len() - 1])
+            .join("\n");
+        let blizzards = ['^', 'v', '<', '>'].map(|b| BitGrid::parse(&inside, |c| c == b));
+        let gap = |line: &str| line.find('.').expect("no gap in the wall") as isize - 1;
+        Self {
+            entrance: Coordinate::new(gap(first), 0),
+            exit: Coordinate::new(gap(last), blizzards[0].height() as isize - 1),
+            blizzards,
         }
     }
-}
 
-#[derive(Copy, Clone, Debug)]
-struct Blizzard {
-    x: usize,
-    y: usize,
-    heading: Heading,
-}
+    /// Moves every blizzard on by one minute.
+    fn blow(&mut self) {
+        for (blizzard, direction) in self.blizzards.iter_mut().zip(DIRECTIONS) {
+            *blizzard = blizzard.rotate(direction);
+        }
+    }
 
-#[derive(Copy, Clone, Debug)]
-enum Heading {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+    /// Returns the minutes it takes to get from outside the valley next to `from` to outside the
+    /// valley next to `to`, tracking every cell the expedition could be in at once.
+    fn cross(&mut self, from: Coordinate, to: Coordinate) -> usize {
+        let bounds = self.blizzards[0].bounds();
+        let mut expedition = BitGrid::new(bounds.width(), bounds.height());
+        for minute in 1.. {
+            expedition = self.spread(&expedition, from);
+            if expedition.get(to) {
+                // Step out of the valley.
+                self.blow();
+                return minute + 1;
+            }
+        }
+        unreachable!()
+    }
 
-impl Blizzard {
-    /// Returns the position of this blizzard at a given time.
-    fn position_at(&self, time: usize, height: usize, width: usize) -> (usize, usize) {
-        match self.heading {
-            Heading::Left => (((time / width + 1) * width + self.x - time) % width, self.y),
-            Heading::Right => ((self.x + time) % width, self.y),
-            Heading::Up => (
-                self.x,
-                ((time / height + 1) * height + self.y - time) % height,
-            ),
-            Heading::Down => (self.x, (self.y + time) % height),
+    /// Moves the blizzards on by a minute and returns where the `expedition`, which entered
+    /// next to `from`, could be after it.
+    fn spread(&mut self, expedition: &BitGrid, from: Coordinate) -> BitGrid {
+        self.blow();
+        let mut reachable = expedition.clone();
+        for direction in DIRECTIONS {
+            reachable |= &expedition.shift(direction);
+        }
+        // The expedition can wait outside for as long as it needs to.
+        reachable.set(from, true);
+        for blizzard in &self.blizzards {
+            reachable &= &!blizzard;
         }
+        reachable
     }
 }
 
@@ -1,8 +1,12 @@
 use itertools::iterate;
 
 pub fn solve() {
-    let input = include_str!("inputs/25.txt");
-    println!("{}", part1(input))
+    let (part1, _) = run(include_str!("inputs/25.txt"));
+    println!("{part1}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    (part1(input).to_string(), String::new())
 }
 
 fn part1(input: &str) -> String {
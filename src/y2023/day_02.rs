@@ -7,8 +7,11 @@ use nom::{
 };
 
 pub fn solve() {
-    let input = include_str!("inputs/day_02");
+    let (part_1, part_2) = run(include_str!("inputs/day_02"));
+    println!("{part_1}\n{part_2}")
+}
 
+pub fn run(input: &str) -> (String, String) {
     let mut sum: u32 = 0;
     let mut sum2: u32 = 0;
 
@@ -20,7 +23,7 @@ pub fn solve() {
         sum2 += record.max_red * record.max_green * record.max_blue;
     });
 
-    println!("{}\n{}", sum as u64, sum2 as u64)
+    ((sum as u64).to_string(), (sum2 as u64).to_string())
 }
 
 #[derive(Default)]
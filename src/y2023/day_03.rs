@@ -5,8 +5,11 @@ use nom::{
 };
 
 pub fn solve() {
-    let input = include_str!("inputs/day_03");
+    let (part_1, part_2) = run(include_str!("inputs/day_03"));
+    println!("{part_1}\n{part_2}")
+}
 
+pub fn run(input: &str) -> (String, String) {
     // Part 1
     let mut sum = 0;
 
@@ -49,7 +52,7 @@ pub fn solve() {
         }
     }
 
-    println!("{}\n{}", sum as u64, sum2 as u64)
+    ((sum as u64).to_string(), (sum2 as u64).to_string())
 }
 
 fn is_part_number(input: &str, x: usize, y: usize, width: usize) -> bool {
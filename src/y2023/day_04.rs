@@ -4,8 +4,11 @@ use nom::{
 };
 
 pub fn solve() {
-    let input = include_str!("inputs/day_04");
+    let (part_1, part_2) = run(include_str!("inputs/day_04"));
+    println!("{part_1}\n{part_2}")
+}
 
+pub fn run(input: &str) -> (String, String) {
     let part_1: u32 = input.lines().map(winnings).map(|n| 2_u32.pow(n - 1)).sum();
 
     let mut part_2 = 0;
@@ -28,7 +31,7 @@ pub fn solve() {
         }
     }
 
-    println!("{}\n{}", part_1 as u64, part_2 as u64)
+    ((part_1 as u64).to_string(), (part_2 as u64).to_string())
 }
 
 fn winnings(mut card: &str) -> u32 {
@@ -1,90 +1,145 @@
-//! This is really slow for part 2, but smarter range analysis would
-//! be tricky with our memory constraints. It does work though, the
-//! solution was computed on real hardware.
-
-use core::ops::Range;
+use crate::interval::{IntervalSet, OffsetMap};
 
 pub fn solve() {
-    let input = include_str!("inputs/day_05");
+    let (part_1, part_2) = run(include_str!("inputs/day_05"));
+    println!("{part_1}\n{part_2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let almanac = Almanac::parse(input);
 
-    let part_1 = input.lines().next().unwrap()[7..]
-        .split_whitespace()
-        .map(|seed| seed_to_location(input, seed.parse().unwrap()))
+    let part_1 = almanac
+        .seeds
+        .iter()
+        .map(|&seed| almanac.location(seed))
         .min()
-        .unwrap_or(u64::MAX);
-
-    let mut seed_ranges: Vec<Range<u64>> = Vec::new();
-    let mut seed_nums = input.lines().next().unwrap()[7..].split_whitespace();
-    while let Some(start) = seed_nums.next() {
-        let start: u64 = start.parse().unwrap();
-        let range: u64 = seed_nums.next().unwrap().parse().unwrap();
-        seed_ranges.push(Range {
-            start,
-            end: start + range,
-        });
-    }
+        .expect("no seeds");
+
+    let seed_ranges: IntervalSet = almanac
+        .seeds
+        .chunks_exact(2)
+        .map(|pair| pair[0]..pair[0] + pair[1])
+        .collect();
+    let part_2 = almanac
+        .locations(&seed_ranges)
+        .min()
+        .expect("no seed ranges");
 
-    let part_2 = (0..)
-        .map(|location| (location, location_to_seed(input, location)))
-        .find(|(_, seed)| seed_ranges.iter().any(|r| r.contains(seed)))
-        .map(|(location, _)| location)
-        .unwrap();
+    (part_1.to_string(), part_2.to_string())
+}
 
-    println!("{}\n{}", part_1, part_2)
+/// The seeds to plant and the maps taking them to locations, which can be queried in either
+/// direction.
+pub struct Almanac {
+    seeds: Vec<i64>,
+    /// Every map from seed to location, in order.
+    stages: Vec<OffsetMap>,
 }
 
-fn seed_to_location(input: &str, seed: u64) -> u64 {
-    let mut current = seed;
-    let mut maps = input.lines().skip(3);
-
-    while let Some(line) = maps.next() {
-        if line.is_empty() {
-            // No matches, current unchanged, move on to next map,
-            // skip the header row.
-            maps.next();
-            continue;
-        }
-
-        let mut triplet = line.split_whitespace();
-
-        let destination_start: u64 = triplet.next().unwrap().parse().unwrap();
-        let source_start: u64 = triplet.next().unwrap().parse().unwrap();
-        let range: u64 = triplet.next().unwrap().parse().unwrap();
-
-        if source_start <= current && current < source_start + range {
-            // Match, update current, skip forward to the next map, skip the header row.
-            current = destination_start + current - source_start;
-            while !maps.next().unwrap_or_default().is_empty() {}
-            maps.next();
-        }
+impl Almanac {
+    pub fn parse(input: &str) -> Self {
+        let mut sections = input.split("\n\n");
+        let seeds = sections
+            .next()
+            .and_then(|line| line.strip_prefix("seeds: "))
+            .expect("no seeds")
+            .split_whitespace()
+            .map(|seed| seed.parse().expect("invalid seed"))
+            .collect();
+        let stages = sections
+            .map(|section| {
+                let mut map = OffsetMap::new();
+                for line in section.lines().skip(1) {
+                    let numbers: Vec<i64> = line
+                        .split_whitespace()
+                        .map(|n| n.parse().expect("invalid map entry"))
+                        .collect();
+                    let [destination, source, length] = numbers[..] else {
+                        panic!("invalid map entry");
+                    };
+                    map.insert(source..source + length, destination - source);
+                }
+                map
+            })
+            .collect();
+        Self { seeds, stages }
+    }
+
+    /// Returns the location of a single `seed`.
+    pub fn location(&self, seed: i64) -> i64 {
+        self.stages.iter().fold(seed, |n, stage| stage.get(n))
     }
 
-    current
+    /// Returns the locations of all `seeds`.
+    pub fn locations(&self, seeds: &IntervalSet) -> IntervalSet {
+        self.stages
+            .iter()
+            .fold(seeds.clone(), |set, stage| stage.apply(&set))
+    }
+
+    /// Returns all seeds ending up in any of `locations`.
+    pub fn seeds_for(&self, locations: &IntervalSet) -> IntervalSet {
+        self.stages
+            .iter()
+            .rev()
+            .fold(locations.clone(), |set, stage| stage.preimage(&set))
+    }
 }
 
-fn location_to_seed(input: &str, location: u64) -> u64 {
-    let mut current = location;
-    let mut maps = input.lines().rev();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
 
-    while let Some(line) = maps.next() {
-        if !line.chars().next().unwrap().is_ascii_digit() {
-            // Reached header, skip forward.
-            maps.next();
-            continue;
-        }
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
 
-        let mut triplet = line.split_whitespace();
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
 
-        let destination_start: u64 = triplet.next().unwrap().parse().unwrap();
-        let source_start: u64 = triplet.next().unwrap().parse().unwrap();
-        let range: u64 = triplet.next().unwrap().parse().unwrap();
+water-to-light map:
+88 18 7
+18 25 70
 
-        if destination_start <= current && current < destination_start + range {
-            // Match, update current, skip forward to the next map.
-            current = source_start + current - destination_start;
-            while !maps.next().unwrap_or_default().is_empty() {}
-        }
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn test_run() {
+        assert_eq!(run(INPUT), ("35".to_string(), "46".to_string()));
     }
 
-    current
+    #[test]
+    fn test_seeds() {
+        let almanac = Almanac::parse(INPUT);
+        let seeds = almanac.seeds_for(&IntervalSet::from(46..47));
+        assert!(seeds.contains(82));
+        assert!(seeds
+            .ranges()
+            .iter()
+            .flat_map(|r| r.clone())
+            .all(|seed| almanac.location(seed) == 46));
+        assert_eq!(almanac.locations(&seeds), IntervalSet::from(46..47));
+    }
 }
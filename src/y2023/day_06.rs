@@ -1,6 +1,9 @@
 pub fn solve() {
-    let input = include_str!("inputs/day_06");
+    let (part_1, part_2) = run(include_str!("inputs/day_06"));
+    println!("{part_1}\n{part_2}")
+}
 
+pub fn run(input: &str) -> (String, String) {
     let times = input
         .lines()
         .next()
@@ -56,7 +59,7 @@ pub fn solve() {
         .unwrap();
 
     let part_2 = losing_too_long - losing_too_short + 1;
-    println!("{}\n{}", part_1, part_2)
+    (part_1.to_string(), part_2.to_string())
 }
 
 #[inline]
@@ -1,8 +1,11 @@
 use core::cmp::Ordering;
 
 pub fn solve() {
-    let input = include_str!("inputs/day_07");
+    let (part_1, part_2) = run(include_str!("inputs/day_07"));
+    println!("{part_1}\n{part_2}")
+}
 
+pub fn run(input: &str) -> (String, String) {
     let mut cards: Vec<(&str, u32)> = Vec::new();
     input
         .lines()
@@ -23,7 +26,7 @@ pub fn solve() {
         .map(|(i, (_hand, bid))| (i as u32 + 1) * bid)
         .sum();
 
-    println!("{}\n{}", part_1 as u64, part_2 as u64)
+    ((part_1 as u64).to_string(), (part_2 as u64).to_string())
 }
 
 fn cmp_hands(left: &str, right: &str, jokers: bool) -> Ordering {
@@ -1,60 +1,164 @@
+use crate::cycle::{find_cycle, Cycle};
+use crate::interner::Interner;
+use crate::math::crt;
+use itertools::Itertools;
+
 pub fn solve() {
-    let input = include_str!("inputs/day_08");
+    let (part_1, part_2) = run(include_str!("inputs/day_08"));
+    println!("{part_1}\n{part_2}")
+}
 
-    let part_1 = navigate(input, "AAA", |pos| pos == "ZZZ");
-    let part_2 = input
-        .lines()
-        .skip(2)
-        .filter(|line| &line[2..3] == "A")
-        .map(|line| navigate(input, &line[..3], |pos| &pos[2..] == "Z"))
-        .fold(1, lcm);
+pub fn run(input: &str) -> (String, String) {
+    let map = Map::parse(input);
+    let part_1 = map.steps(|name| name == "AAA", |name| name == "ZZZ");
+    let part_2 = map.steps(|name| name.ends_with('A'), |name| name.ends_with('Z'));
 
-    println!("{}\n{}", part_1, part_2)
+    (part_1.to_string(), part_2.to_string())
 }
 
-fn navigate(input: &str, start: &str, pred: impl Fn(&str) -> bool) -> u64 {
-    let mut route = input.lines().next().unwrap().chars().cycle();
-    let mut position = start;
-    let mut steps = 0;
-    while !pred(position) {
-        steps += 1;
-        match route.next() {
-            Some('L') => {
-                position = &input
-                    .lines()
-                    .find(|line| line.starts_with(position))
-                    .unwrap()[7..10];
-            }
-            Some('R') => {
-                position = &input
-                    .lines()
-                    .find(|line| line.starts_with(position))
-                    .unwrap()[12..15];
-            }
-            _ => panic!("invalid direction"),
+struct Map<'a> {
+    route: Vec<usize>,
+    nodes: Interner<'a, u16>,
+    /// The left and right destination of every node.
+    network: Vec<[u16; 2]>,
+}
+
+impl<'a> Map<'a> {
+    fn parse(input: &'a str) -> Self {
+        let (route, network) = input.split_once("\n\n").expect("no network");
+        let route = route
+            .chars()
+            .map(|c| match c {
+                'L' => 0,
+                'R' => 1,
+                _ => panic!("invalid direction"),
+            })
+            .collect();
+        let mut nodes = Interner::new();
+        let mut edges = Vec::new();
+        for line in network.lines() {
+            let node = nodes.intern(&line[..3]);
+            let left = nodes.intern(&line[7..10]);
+            let right = nodes.intern(&line[12..15]);
+            edges.push((node, [left, right]));
+        }
+        let mut network = vec![[0; 2]; nodes.len()];
+        for (node, destinations) in edges {
+            network[node as usize] = destinations;
+        }
+        Self {
+            route,
+            nodes,
+            network,
         }
     }
-    steps
+
+    /// Returns the first step at which every ghost walking from a start is at an end, walking
+    /// each ghost's path until it loops and combining the loops.
+    fn steps(&self, is_start: impl Fn(&str) -> bool, is_end: impl Fn(&str) -> bool) -> i64 {
+        let ghosts: Vec<Ghost> = self
+            .nodes
+            .ids()
+            .filter(|&node| is_start(self.nodes.name(node)))
+            .map(|start| self.haunt(start, &is_end))
+            .collect();
+
+        // Every end reached before all ghosts loop.
+        let before_loops = ghosts
+            .iter()
+            .flat_map(|ghost| &ghost.ends)
+            .filter(|&&end| ghosts.iter().all(|ghost| ghost.at_end(end)))
+            .min()
+            .copied();
+
+        // Otherwise, every combination of ends within each loop.
+        let settled = ghosts.iter().map(|g| g.cycle.start).max().unwrap_or(0);
+        let in_loops = ghosts
+            .iter()
+            .map(|ghost| {
+                ghost
+                    .ends
+                    .iter()
+                    .filter(|&&end| end >= ghost.cycle.start)
+                    .map(|&end| (end as i64, ghost.cycle.length as i64))
+            })
+            .multi_cartesian_product()
+            .filter_map(crt)
+            .map(|(step, period)| {
+                let behind = (settled as i64 - step).max(0);
+                step + (behind + period - 1) / period * period
+            })
+            .min();
+
+        before_loops
+            .map(|end| end as i64)
+            .into_iter()
+            .chain(in_loops)
+            .min()
+            .expect("ghosts never meet at the ends")
+    }
+
+    fn haunt(&self, start: u16, is_end: impl Fn(&str) -> bool) -> Ghost {
+        let mut ends = Vec::new();
+        let mut state = (start, 0);
+        let cycle = find_cycle(
+            &mut state,
+            |(node, step)| {
+                if is_end(self.nodes.name(*node)) {
+                    ends.push(*step);
+                }
+                *node = self.network[*node as usize][self.route[*step % self.route.len()]];
+                *step += 1;
+            },
+            |&(node, step)| (node, step % self.route.len()),
+        );
+        Ghost { cycle, ends }
+    }
 }
 
-fn lcm(first: u64, second: u64) -> u64 {
-    first * second / gcd(first, second)
+/// Where a ghost's path loops, and every step at which it's at an end up to there.
+struct Ghost {
+    cycle: Cycle,
+    ends: Vec<usize>,
 }
 
-fn gcd(first: u64, second: u64) -> u64 {
-    let mut max = first;
-    let mut min = second;
-    if min > max {
-        core::mem::swap(&mut max, &mut min);
+impl Ghost {
+    fn at_end(&self, step: usize) -> bool {
+        self.ends.binary_search(&self.cycle.reduce(step)).is_ok()
     }
+}
 
-    loop {
-        let res = max % min;
-        if res == 0 {
-            return min;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_1() {
+        let input = "\
+LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)";
+        assert_eq!(run(input).0, "6");
+    }
+
+    #[test]
+    fn test_part_2() {
+        let input = "\
+LR
 
-        max = min;
-        min = res;
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        assert_eq!(
+            Map::parse(input).steps(|name| name.ends_with('A'), |name| name.ends_with('Z')),
+            6
+        );
     }
 }
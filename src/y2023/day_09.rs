@@ -1,6 +1,9 @@
 pub fn solve() {
-    let input = include_str!("inputs/day_09");
+    let (part_1, part_2) = run(include_str!("inputs/day_09"));
+    println!("{part_1}\n{part_2}")
+}
 
+pub fn run(input: &str) -> (String, String) {
     let part_1: i64 = input
         .lines()
         .map(|line| extrapolate(line, Direction::Back))
@@ -10,7 +13,7 @@ pub fn solve() {
         .map(|line| extrapolate(line, Direction::Front))
         .sum();
 
-    println!("{}\n{}", part_1 as u64, part_2 as u64)
+    ((part_1 as u64).to_string(), (part_2 as u64).to_string())
 }
 
 fn extrapolate(line: &str, direction: Direction) -> i64 {
@@ -1,20 +1,27 @@
+use crate::coordinate::Coordinate;
+use crate::polygon::Polygon;
+
 pub fn solve() {
-    let input = include_str!("inputs/day_10");
+    let (part_1, part_2) = run(include_str!("inputs/day_10"));
+    println!("{part_1}\n{part_2}")
+}
+
+pub fn run(input: &str) -> (String, String) {
     let map = Map {
         s: input.as_bytes(),
         width: input.lines().next().unwrap().len() + 1,
     };
 
-    let mut steps = 0;
     let start = map.start();
     let mut position = start;
+    let mut path = vec![];
 
     // Slightly fudging it here, normally we'd have to find a valid
     // direction to walk into, but that's just a bunch of ifs.
     let mut direction = Direction::Right;
 
     loop {
-        steps += 1;
+        path.push(Coordinate::new(position.0 as isize, position.1 as isize));
         position = walk(position, direction);
         if position == start {
             break;
@@ -22,7 +29,12 @@ pub fn solve() {
         direction = turn(direction, map.get_field(position));
     }
 
-    println!("{}\n{}", steps / 2, 0)
+    // The pipe goes through the middle of its tiles, so those enclosed are the grid points inside.
+    let pipe = Polygon::new(path.iter().copied());
+    (
+        (path.len() / 2).to_string(),
+        pipe.interior_points().to_string(),
+    )
 }
 
 type Coordinates = (usize, usize);
@@ -103,3 +115,34 @@ fn turn(direction: Direction, space: Field) -> Direction {
         _ => panic!("Invalid direction"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_square() {
+        let input = "\
+.....
+.S-7.
+.|.|.
+.L-J.
+.....";
+        assert_eq!(run(input), ("4".to_string(), "1".to_string()));
+    }
+
+    #[test]
+    fn test_enclosed() {
+        let input = "\
+..........
+.S------7.
+.|F----7|.
+.||....||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........";
+        assert_eq!(run(input).1, "4");
+    }
+}
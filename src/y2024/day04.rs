@@ -1,87 +1,26 @@
-use itertools::Itertools;
+use crate::grid::Grid;
 
 crate::solve!("04");
 
-fn parse(input: &str) -> Search {
-    Search::new(input)
+fn parse(input: &str) -> Grid<char> {
+    Grid::parse(input, |c| c)
 }
 
-fn part_1(search: &Search) -> usize {
-    search.count_xmas()
+fn part_1(search: &Grid<char>) -> usize {
+    count_matches(search, &["XMAS", "X...\n.M..\n..A.\n...S"])
 }
 
-fn part_2(search: &Search) -> usize {
-    search.count_x_mas()
+fn part_2(search: &Grid<char>) -> usize {
+    count_matches(search, &["M.S\n.A.\nM.S"])
 }
 
-struct Search {
-    width: isize,
-    data: Vec<char>,
-}
-
-impl Search {
-    fn new(s: &str) -> Self {
-        let data: Vec<_> = s.chars().filter(|c| "XMAS".contains(*c)).collect();
-        let width = s.find('\n').expect("no newline found") as isize;
-        Self { width, data }
-    }
-
-    fn count_xmas(&self) -> usize {
-        let offsets = [
-            -self.width - 1,
-            -self.width,
-            -self.width + 1,
-            -1,
-            1,
-            self.width - 1,
-            self.width,
-            self.width + 1,
-        ];
-        let column = |idx| idx % self.width;
-        let is_xmas = |start: isize, offset: isize| {
-            if ![0, 3].contains(&(column(start)).abs_diff(column(start + 3 * offset))) {
-                // Avoid wrapping over the side edges.
-                return false;
-            };
-            (1..=3)
-                .filter_map(|step| self.data.get((start + step * offset) as usize))
-                .collect::<String>()
-                == "MAS"
-        };
-        self.data
-            .iter()
-            .positions(|c| *c == 'X')
-            .map(|i| {
-                offsets
-                    .iter()
-                    .filter(move |o| is_xmas(i as isize, **o))
-                    .count()
-            })
-            .sum()
-    }
-
-    fn count_x_mas(&self) -> usize {
-        let offsets = [
-            (-self.width - 1, self.width + 1),
-            (-self.width + 1, self.width - 1),
-        ];
-        let is_x_mas = |a: isize| {
-            if [0, self.width - 1].contains(&(a % self.width)) {
-                return false;
-            }
-            offsets.iter().all(|(x, y)| {
-                [['M', 'S'], ['S', 'M']].contains(&[
-                    *self.data.get((a + x) as usize).unwrap_or(&' '),
-                    *self.data.get((a + y) as usize).unwrap_or(&' '),
-                ])
-            })
-        };
-        self.data
-            .iter()
-            .positions(|c| *c == 'A')
-            .filter(|i| is_x_mas(*i as isize))
-            .count()
-    }
+/// Counts matches of `patterns` in every orientation, with `.` matching anything.
+fn count_matches(search: &Grid<char>, patterns: &[&str]) -> usize {
+    patterns
+        .iter()
+        .flat_map(|p| Grid::parse(p, |c| (c != '.').then_some(c)).orientations())
+        .map(|p| search.matches(&p).count())
+        .sum()
 }
 
 #[cfg(test)]
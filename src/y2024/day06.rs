@@ -1,150 +1,85 @@
-use itertools::Itertools;
+use crate::coordinate::{walk_within, Coordinate, Direction, Pose, Walk};
+use crate::grid::Grid;
+use crate::replay::Simulation;
+use crate::viz::{Image, Rgb};
 use rayon::prelude::*;
-use rustc_hash::FxHashSet;
 
 crate::solve!("06");
 
-fn parse(input: &str) -> &str {
-    input
+fn parse(input: &str) -> (Grid<char>, Pose) {
+    let map = Grid::parse(input, |c| c);
+    let start = map.find(|&c| c == '^').expect("start not found");
+    (map, Pose::new(start, Direction::Up))
 }
 
-fn part_1(input: &str) -> usize {
-    Map::from_str(input).walk().len()
+fn part_1((map, start): &(Grid<char>, Pose)) -> usize {
+    patrol(map, *start, None).positions().len()
 }
 
-fn part_2(input: &str) -> usize {
-    let mut map = Map::from_str(input);
-    let initial_position = map.position;
-    let initial_direction = map.direction;
-
-    let normally_visited = map.walk();
-    map.position = initial_position;
-    map.direction = initial_direction;
-
-    normally_visited
-        .par_iter()
-        .filter(|&idx| *idx != initial_position)
-        .filter(|&idx| {
-            let mut map = map.clone();
-            map.inner[*idx] = '#';
-            map.will_loop()
-        })
+fn part_2((map, start): &(Grid<char>, Pose)) -> usize {
+    patrol(map, *start, None)
+        .positions()
+        .into_par_iter()
+        .filter(|&position| position != start.position)
+        .filter(|&position| patrol(map, *start, Some(position)).looped)
         .count()
 }
 
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-#[derive(Clone)]
-struct Map {
-    width: usize,
-    inner: Vec<char>,
-    position: usize,
-    direction: Direction,
+/// Walk until leaving or looping, optionally with an extra obstacle placed.
+fn patrol(map: &Grid<char>, start: Pose, obstacle: Option<Coordinate>) -> Walk {
+    walk_within(start, map.bounds(), |guard| next_pose(map, guard, obstacle))
 }
 
-impl Map {
-    fn from_str(s: &str) -> Self {
-        let width = s.lines().next().expect("no lines").len();
-        let inner = s.chars().filter(|&c| ".#^".contains(c)).collect_vec();
-        let position = inner
-            .iter()
-            .position(|&c| c == '^')
-            .expect("start not found");
-        Self {
-            width,
-            inner,
-            position,
-            direction: Direction::Up,
-        }
+/// Returns where the guard goes next, or `None` if it leaves the map.
+fn next_pose(map: &Grid<char>, guard: Pose, obstacle: Option<Coordinate>) -> Option<Pose> {
+    let ahead = guard.ahead();
+    // Turn when facing an obstacle, but don't walk yet, so we can record the new direction in
+    // loop detection.
+    match map.get(ahead)? {
+        '#' => Some(guard.turn_right()),
+        _ if obstacle == Some(ahead) => Some(guard.turn_right()),
+        _ => Some(guard.step()),
     }
+}
 
-    /// Walk until leaving or looping, returning whether looped.
-    fn will_loop(&mut self) -> bool {
-        let mut locations = [[false; 4]; 16_900];
-        while self.step() {
-            if locations[self.position][self.direction as usize] {
-                return true;
-            }
-            locations[self.position][self.direction as usize] = true;
-        }
-        false
-    }
+/// Follows the guard of part 1 until it leaves the map.
+pub fn replay() -> Box<dyn Simulation> {
+    let (map, guard) = parse(include_str!("inputs/06"));
+    let mut visited = Grid::new(map.width(), map.height(), false);
+    visited[guard.position] = true;
+    Box::new(Replay {
+        map,
+        guard,
+        visited,
+    })
+}
 
-    /// Walk until leaving map, returning all positions visited.
-    fn walk(&mut self) -> FxHashSet<usize> {
-        let mut locations = FxHashSet::from_iter([self.position]);
-        locations.reserve(10_000);
-        while self.step() {
-            locations.insert(self.position);
-        }
-        locations
-    }
+struct Replay {
+    map: Grid<char>,
+    guard: Pose,
+    visited: Grid<bool>,
+}
 
+impl Simulation for Replay {
     fn step(&mut self) -> bool {
-        // Are we leaving as is?
-        if self.is_leaving() {
-            return false;
-        }
-        // If not facing an obstacle, walk and done
-        if !self.is_facing_obstacle() {
-            self.position = self.next_position();
-            return true;
-        }
-        // If facing an obstacle, turn.
-        self.turn();
-        // Are we leaving after turning?
-        if self.is_leaving() {
+        let Some(guard) = next_pose(&self.map, self.guard, None) else {
             return false;
-        }
-        // If we aren't facing an obstacle after turning once, walk. Otherwise, leave it at turning,
-        // so we can record the new direction in loop detection.
-        if !self.is_facing_obstacle() {
-            self.position = self.next_position();
-        }
+        };
+        self.guard = guard;
+        self.visited[guard.position] = true;
         true
     }
 
-    fn turn(&mut self) {
-        self.direction = match self.direction {
-            Direction::Down => Direction::Left,
-            Direction::Up => Direction::Right,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
-    }
-
-    /// Not guaranteed to be in bounds/non-wrapping.
-    fn next_position(&self) -> usize {
-        match self.direction {
-            Direction::Down => self.position + self.width,
-            Direction::Up => self.position - self.width,
-            Direction::Left => self.position - 1,
-            Direction::Right => self.position + 1,
-        }
-    }
-
-    fn is_leaving(&self) -> bool {
-        match self.direction {
-            Direction::Right => self.position % self.width == self.width - 1,
-            Direction::Left => self.position % self.width == 0,
-            Direction::Up => self.position < self.width,
-            Direction::Down => self.position >= self.inner.len() - self.width,
-        }
-    }
-
-    /// Assumes we're not about to walk off the map, otherwise will show phantom obstacles due to
-    /// wrapping.
-    fn is_facing_obstacle(&self) -> bool {
-        self.inner
-            .get(self.next_position())
-            .map(|&c| c == '#')
-            .unwrap_or(false)
+    fn image(&self) -> Image {
+        let mut image = Image::from_grid(&self.map, |&c| match c {
+            '#' => Rgb::GREY,
+            _ => Rgb::BLACK,
+        });
+        self.visited
+            .positions(|&v| v)
+            .for_each(|c| image.set(c, Rgb::BLUE));
+        image.set(self.guard.position, Rgb::RED);
+        image
     }
 }
 
@@ -166,11 +101,11 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        assert_eq!(part_1(parse(INPUT)), 41);
+        assert_eq!(part_1(&parse(INPUT)), 41);
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part_2(parse(INPUT)), 6);
+        assert_eq!(part_2(&parse(INPUT)), 6);
     }
 }
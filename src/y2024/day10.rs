@@ -23,7 +23,7 @@ impl Map {
         if position >= self.width {
             rv.push(position - self.width);
         }
-        if position % self.width != 0 {
+        if !position.is_multiple_of(self.width) {
             rv.push(position - 1);
         }
         if position % self.width != self.width - 1 {
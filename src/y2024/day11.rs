@@ -1,3 +1,4 @@
+use crate::memo::Memo;
 use nom::{
     character::complete::{space1, u64 as parse_u64},
     multi::separated_list1,
@@ -12,32 +13,33 @@ fn parse(input: &str) -> Vec<u64> {
 }
 
 fn part_1(stones: &[u64]) -> usize {
-    stones.iter().map(|&s| stone_size((s, 25))).sum()
+    let mut memo = Memo::new();
+    stones.iter().map(|&s| stone_size(&mut memo, s, 25)).sum()
 }
 
 fn part_2(stones: &[u64]) -> usize {
-    stones.iter().map(|&s| stone_size((s, 75))).sum()
+    let mut memo = Memo::new();
+    stones.iter().map(|&s| stone_size(&mut memo, s, 75)).sum()
 }
 
-#[memoize::memoize(CustomHasher: ahash::AHashMap)]
-fn stone_size(key: (u64, usize)) -> usize {
-    let (stone, iterations) = key;
+fn stone_size(memo: &mut Memo<(u64, usize), usize>, stone: u64, iterations: usize) -> usize {
     if iterations == 0 {
         return 1;
     }
-
-    if stone == 0 {
-        return stone_size((1, iterations - 1));
-    }
-
-    let digits = stone.checked_ilog10().unwrap_or(0) + 1;
-    if digits % 2 == 0 {
-        let a = stone_size((stone / 10_u64.pow(digits / 2), iterations - 1));
-        let b = stone_size((stone % 10_u64.pow(digits / 2), iterations - 1));
-        return a + b;
-    }
-
-    stone_size((stone * 2024, iterations - 1))
+    memo.get_or_insert_with((stone, iterations), |memo| {
+        if stone == 0 {
+            return stone_size(memo, 1, iterations - 1);
+        }
+
+        let digits = stone.checked_ilog10().unwrap_or(0) + 1;
+        if digits.is_multiple_of(2) {
+            let a = stone_size(memo, stone / 10_u64.pow(digits / 2), iterations - 1);
+            let b = stone_size(memo, stone % 10_u64.pow(digits / 2), iterations - 1);
+            return a + b;
+        }
+
+        stone_size(memo, stone * 2024, iterations - 1)
+    })
 }
 
 #[cfg(test)]
@@ -1,50 +1,19 @@
+use crate::coordinate::{Coordinate, DIRECTIONS};
+use crate::grid::Grid;
+use crate::polygon::{trace_region, Polygon};
+use crate::unionfind::grid_components;
 use rustc_hash::FxHashSet;
 
 crate::solve!("12");
 
-type Plant = char;
-type Plot = (usize, usize);
-type Region = FxHashSet<Plot>;
+type Region = FxHashSet<Coordinate>;
 
 fn parse(input: &str) -> Vec<Region> {
-    let mut plots = FxHashSet::default();
-    for (y, line) in input.lines().enumerate() {
-        for (x, plant) in line.chars().enumerate() {
-            plots.insert((plant, (x, y)));
-        }
-    }
-    let mut regions = vec![];
-    while !plots.is_empty() {
-        let p = *plots.iter().next().unwrap();
-        let plot = plots.take(&p).unwrap();
-        let mut region = FxHashSet::from_iter([plot.1]);
-        fill_region(plot, &mut plots, &mut region);
-        regions.push(region);
-    }
-    regions
-}
-
-fn fill_region(
-    (plant, (x, y)): (Plant, Plot),
-    from: &mut FxHashSet<(Plant, Plot)>,
-    to: &mut Region,
-) {
-    [
-        (x.wrapping_sub(1), y),
-        (x + 1, y),
-        (x, y.wrapping_sub(1)),
-        (x, y + 1),
-    ]
-    .iter()
-    .for_each(|(x, y)| {
-        if to.contains(&(*x, *y)) {
-            return;
-        }
-        if let Some(p) = from.take(&(plant, (*x, *y))) {
-            to.insert(p.1);
-            fill_region(p, from, to)
-        }
-    });
+    let garden = Grid::parse(input, |plant| plant);
+    grid_components(&garden, |a, b| a == b)
+        .into_iter()
+        .map(Region::from_iter)
+        .collect()
 }
 
 fn part_1(regions: &[Region]) -> usize {
@@ -57,79 +26,21 @@ fn part_2(regions: &[Region]) -> usize {
 
 fn price(region: &Region) -> usize {
     let area = region.len();
-    let perimeter: usize = region
+    let perimeter = region
         .iter()
-        .map(|&(x, y)| {
-            [
-                (x.wrapping_sub(1), y),
-                (x + 1, y),
-                (x, y.wrapping_sub(1)),
-                (x, y + 1),
-            ]
-            .iter()
-            .filter(|&neighbour| !region.contains(neighbour))
-            .count()
-        })
-        .sum();
+        .flat_map(|&plot| DIRECTIONS.map(|direction| plot + direction))
+        .filter(|neighbour| !region.contains(neighbour))
+        .count();
     area * perimeter
 }
 
 fn price_with_bulk_discount(region: &Region) -> usize {
     let area = region.len();
-    let corners: usize = region
+    let sides: usize = trace_region(region.iter().copied())
         .iter()
-        .filter(|&plot| is_perimeter(plot, region))
-        .map(|&plot| external_corners(plot, region))
+        .map(Polygon::sides)
         .sum();
-    area * corners
-}
-
-/// True if this plot has a non-region plot anywhere in its eight surrounding spaces.
-fn is_perimeter(&(x, y): &Plot, region: &Region) -> bool {
-    [
-        (x.wrapping_sub(1), y.wrapping_sub(1)),
-        (x.wrapping_sub(1), y),
-        (x.wrapping_sub(1), y + 1),
-        (x, y + 1),
-        (x, y.wrapping_sub(1)),
-        (x + 1, y.wrapping_sub(1)),
-        (x + 1, y),
-        (x + 1, y + 1),
-    ]
-    .iter()
-    .any(|neighbour| !region.contains(neighbour))
-}
-
-/// Counts the corners of this plot that are either convex or concave corners of the
-/// entire region, by looking at two adjacent non-diagonal neighbours as well as the
-/// diagonal one between them.
-fn external_corners((x, y): Plot, region: &Region) -> usize {
-    [
-        (
-            (x.wrapping_sub(1), y),
-            (x, y.wrapping_sub(1)),
-            (x.wrapping_sub(1), y.wrapping_sub(1)),
-        ),
-        (
-            (x, y.wrapping_sub(1)),
-            (x + 1, y),
-            (x + 1, y.wrapping_sub(1)),
-        ),
-        ((x + 1, y), (x, y + 1), (x + 1, y + 1)),
-        (
-            (x, y + 1),
-            (x.wrapping_sub(1), y),
-            (x.wrapping_sub(1), y + 1),
-        ),
-    ]
-    .iter()
-    .filter(|(adj_a, adj_b, diag)| {
-        let a = region.contains(adj_a);
-        let b = region.contains(adj_b);
-        let c = region.contains(diag);
-        a && b && !c || !a && !b
-    })
-    .count()
+    area * sides
 }
 
 #[cfg(test)]
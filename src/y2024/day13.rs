@@ -1,38 +1,39 @@
+use crate::parsing::{blocks, field, number, parse_all, PResult};
 use nom::{
-    bytes::complete::tag, character::complete::i64 as parse_u32, multi::separated_list0, IResult,
+    bytes::complete::tag,
+    character::complete::line_ending,
+    combinator::map,
+    sequence::{pair, preceded, separated_pair, tuple},
 };
 
 crate::solve!("13");
 
 fn parse(input: &str) -> Vec<Machine> {
-    let parse_machine = |s| -> IResult<&str, Machine> {
-        let (s, _) = tag("Button A: X+")(s)?;
-        let (s, a_x) = parse_u32(s)?;
-        let (s, _) = tag(", Y+")(s)?;
-        let (s, a_y) = parse_u32(s)?;
-        let (s, _) = tag("\nButton B: X+")(s)?;
-        let (s, b_x) = parse_u32(s)?;
-        let (s, _) = tag(", Y+")(s)?;
-        let (s, b_y) = parse_u32(s)?;
-        let (s, _) = tag("\nPrize: X=")(s)?;
-        let (s, prize_x) = parse_u32(s)?;
-        let (s, _) = tag(", Y=")(s)?;
-        let (s, prize_y) = parse_u32(s)?;
-        Ok((
-            s,
-            Machine {
-                a: Coord { x: a_x, y: a_y },
-                b: Coord { x: b_x, y: b_y },
-                prize: Coord {
-                    x: prize_x,
-                    y: prize_y,
-                },
-            },
-        ))
-    };
-    separated_list0(tag("\n\n"), parse_machine)(input)
-        .expect("parse failed")
-        .1
+    let machine = map(
+        tuple((
+            field("Button A", offset("+")),
+            line_ending,
+            field("Button B", offset("+")),
+            line_ending,
+            field("Prize", offset("=")),
+        )),
+        |(a, _, b, _, prize)| Machine { a, b, prize },
+    );
+    parse_all(input, blocks(machine))
+}
+
+/// Parses e.g. `X+94, Y+34` with the `+` as `sign`.
+fn offset(sign: &'static str) -> impl FnMut(&str) -> PResult<'_, Coord> {
+    move |s| {
+        map(
+            separated_pair(
+                preceded(pair(tag("X"), tag(sign)), number),
+                pair(tag(", Y"), tag(sign)),
+                number,
+            ),
+            |(x, y)| Coord { x, y },
+        )(s)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1,90 +1,116 @@
+use crate::coordinate::{Bounds, Coordinate};
+use crate::math::crt;
+use crate::parsing::{coordinate, lines, parse_all};
+use crate::replay::Simulation;
+use crate::viz::{Frames, Image, Rgb};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
-    character::complete::{char, i32 as parse_i32, space1},
-    sequence::{preceded, separated_pair},
-    IResult,
+    character::complete::char,
+    combinator::map,
+    sequence::{pair, preceded},
 };
 
 crate::solve!("14");
 
 fn parse(input: &str) -> Vec<Robot> {
-    let parse_robot = |s| -> IResult<&str, Robot> {
-        let (s, position) =
-            preceded(tag("p="), separated_pair(parse_i32, char(','), parse_i32))(s)?;
-        let (s, _) = space1(s)?;
-        let (s, velocity) =
-            preceded(tag("v="), separated_pair(parse_i32, char(','), parse_i32))(s)?;
-        Ok((
-            s,
-            Robot {
-                position: XY {
-                    x: position.0,
-                    y: position.1,
-                },
-                velocity: XY {
-                    x: velocity.0,
-                    y: velocity.1,
-                },
-            },
-        ))
-    };
-    input
-        .lines()
-        .map(|l| parse_robot(l).expect("invalid robot").1)
-        .collect()
+    let robot = map(
+        pair(
+            preceded(tag("p="), coordinate(char(','))),
+            preceded(tag(" v="), coordinate(char(','))),
+        ),
+        |(position, velocity)| Robot { position, velocity },
+    );
+    parse_all(input, lines(robot))
 }
 
 fn part_1(robots: &[Robot]) -> usize {
-    let size = if robots.len() > 100 {
-        (101, 103)
+    let bounds = if robots.len() > 100 {
+        Bounds::from_size(101, 103)
     } else {
-        (11, 7)
+        Bounds::from_size(11, 7)
     };
     let mut robots = robots.to_vec();
-    (0..100).for_each(|_| robots.iter_mut().for_each(|b| b.step(size)));
-    safety_factor(&robots, size)
+    let mut frames = Frames::new("2024-14-part1");
+    (0..100).for_each(|_| {
+        robots.iter_mut().for_each(|b| b.step(bounds));
+        frames.record(|| image(&robots, bounds).scaled(4));
+    });
+    safety_factor(&robots, bounds)
 }
 
-fn part_2(robots: &[Robot]) -> usize {
-    let size = if robots.len() > 10 {
-        (101, 103)
+fn part_2(robots: &[Robot]) -> i64 {
+    let bounds = if robots.len() > 10 {
+        Bounds::from_size(101, 103)
     } else {
-        (11, 7)
+        Bounds::from_size(11, 7)
     };
-    let mut robots = robots.to_vec();
-    let mut steps = 0;
-    while !has_line(&robots) {
-        steps += 1;
-        robots.iter_mut().for_each(|b| b.step(size));
-    }
-    steps
+    // The robots bunch up into the tree on both axes at once, and each axis repeats on its own,
+    // so find the step where each axis is most bunched up and combine them.
+    let x = tightest_step(robots, bounds.width(), |c| c.x);
+    let y = tightest_step(robots, bounds.height(), |c| c.y);
+    let step = crt([(x, bounds.width() as i64), (y, bounds.height() as i64)])
+        .expect("axes never line up")
+        .0;
+    Frames::new("2024-14-part2").record(|| {
+        let mut robots = robots.to_vec();
+        let step = step as isize;
+        robots.iter_mut().for_each(|b| {
+            let offset = Coordinate::new(b.velocity.x * step, b.velocity.y * step);
+            b.position = b.position.wrapping_add_within(offset, bounds);
+        });
+        image(&robots, bounds).scaled(4)
+    });
+    step
 }
 
-#[derive(Copy, Clone, Debug)]
-struct XY {
-    x: i32,
-    y: i32,
+/// Moves the robots until their positions repeat. Jump to the answer of part 2 for the tree.
+pub fn replay() -> Box<dyn Simulation> {
+    let robots = parse(include_str!("inputs/14"));
+    Box::new(Replay {
+        robots,
+        bounds: Bounds::from_size(101, 103),
+        steps: 0,
+    })
+}
+
+struct Replay {
+    robots: Vec<Robot>,
+    bounds: Bounds,
+    steps: usize,
+}
+
+impl Simulation for Replay {
+    fn step(&mut self) -> bool {
+        self.steps < self.bounds.width() * self.bounds.height() && {
+            self.robots.iter_mut().for_each(|b| b.step(self.bounds));
+            self.steps += 1;
+            true
+        }
+    }
+
+    fn image(&self) -> Image {
+        image(&self.robots, self.bounds)
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
 struct Robot {
-    position: XY,
-    velocity: XY,
+    position: Coordinate,
+    velocity: Coordinate,
 }
 
 impl Robot {
-    fn step(&mut self, (width, height): (usize, usize)) {
-        self.position.x = (width as i32 + self.position.x + self.velocity.x) % width as i32;
-        self.position.y = (height as i32 + self.position.y + self.velocity.y) % height as i32;
+    fn step(&mut self, bounds: Bounds) {
+        self.position = self.position.wrapping_add_within(self.velocity, bounds);
     }
 }
 
-fn safety_factor(robots: &[Robot], (width, height): (usize, usize)) -> usize {
+fn safety_factor(robots: &[Robot], bounds: Bounds) -> usize {
     let quadrants = robots.iter().into_group_map_by(|b| {
         match (
-            ((width as i32) / 2 - b.position.x).signum(),
-            ((height as i32) / 2 - b.position.y).signum(),
+            (bounds.max.x / 2 - b.position.x).signum(),
+            (bounds.max.y / 2 - b.position.y).signum(),
         ) {
             (-1, -1) => 1,
             (-1, 1) => 2,
@@ -98,23 +124,27 @@ fn safety_factor(robots: &[Robot], (width, height): (usize, usize)) -> usize {
         .product()
 }
 
-fn has_line(robots: &[Robot]) -> bool {
-    let mut y_positions = [0; 103];
-    for robot in robots {
-        y_positions[robot.position.y as usize] += 1;
-    }
-    let busiest_y = y_positions.iter().position_max().unwrap_or_default() as i32;
-    if y_positions[busiest_y as usize] < 20 {
-        return false;
-    }
-    20 < robots
+fn image(robots: &[Robot], bounds: Bounds) -> Image {
+    let mut image = Image::new(bounds.width(), bounds.height(), Rgb::BLACK);
+    robots
         .iter()
-        .filter(|b| b.position.y == busiest_y)
-        .map(|b| b.position.x)
-        .sorted_unstable()
-        .tuple_windows()
-        .filter(|(a, b)| a.abs_diff(*b) == 1)
-        .count()
+        .for_each(|b| image.set(b.position - bounds.min, Rgb::GREEN));
+    image
+}
+
+/// Returns the step within one `period` where the robots' positions along `axis` vary least.
+fn tightest_step(robots: &[Robot], period: usize, axis: fn(Coordinate) -> isize) -> i64 {
+    let period = period as isize;
+    let spread = |step: isize| {
+        let (sum, squares) = robots
+            .iter()
+            .map(|b| (axis(b.position) + axis(b.velocity) * step).rem_euclid(period))
+            .fold((0, 0), |(sum, squares), p| (sum + p, squares + p * p));
+        robots.len() as isize * squares - sum * sum
+    };
+    (0..period)
+        .min_by_key(|&step| spread(step))
+        .unwrap_or_default() as i64
 }
 
 #[cfg(test)]
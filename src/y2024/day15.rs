@@ -1,55 +1,55 @@
+use crate::coordinate::{Bounds, Coordinate, Direction, LEFT, RIGHT};
+use crate::replay::Simulation;
+use crate::viz::{Frames, Image, Rgb};
 use rustc_hash::FxHashSet;
 
 crate::solve!("15");
 
-fn parse(input: &str) -> (Map, Coord, Vec<Direction>) {
-    let parse_instruction = |c| match c {
-        '^' => Direction::Up,
-        'v' => Direction::Down,
-        '<' => Direction::Left,
-        '>' => Direction::Right,
-        _ => unreachable!(),
-    };
+/// How many instructions to follow between frames of the visualization.
+const FRAME_INTERVAL: usize = 100;
 
+fn parse(input: &str) -> (Map, Coordinate, Vec<Direction>) {
     let (map, instructions) = input.split_once("\n\n").expect("no divider");
 
     let mut walls = FxHashSet::default();
     let mut boxes = FxHashSet::default();
-    let mut robot = (0, 0);
-    for (y, line) in map.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '#' => {
-                    walls.insert((x as isize, y as isize));
-                }
-                'O' => {
-                    boxes.insert((x as isize, y as isize));
-                }
-                '@' => robot = (x as isize, y as isize),
-                _ => {}
+    let mut robot = Coordinate::default();
+    for (position, c) in Coordinate::parse_grid(map, Some) {
+        match c {
+            '#' => {
+                walls.insert(position);
             }
+            'O' => {
+                boxes.insert(position);
+            }
+            '@' => robot = position,
+            _ => {}
         }
     }
 
     let instructions = instructions
         .chars()
         .filter(|c| !c.is_whitespace())
-        .map(parse_instruction)
+        .map(|c| Direction::try_from(c).expect("invalid instruction"))
         .collect();
 
     (Map { walls, boxes }, robot, instructions)
 }
 
-fn part_1((map, robot, instructions): &(Map, Coord, Vec<Direction>)) -> isize {
+fn part_1((map, robot, instructions): &(Map, Coordinate, Vec<Direction>)) -> isize {
     let mut map = map.to_owned();
     let mut robot = robot.to_owned();
 
-    'ins: for instruction in instructions {
-        let diff = instruction.diff();
+    let mut frames = Frames::new("2024-15-part1");
+    'ins: for (i, instruction) in instructions.iter().enumerate() {
+        if i.is_multiple_of(FRAME_INTERVAL) {
+            frames.record(|| map.image(robot, 1).scaled(4));
+        }
+        let diff = instruction.offset();
         let mut boxes_to_move = vec![];
         let mut current = robot;
         'bxs: loop {
-            let next = add_coords(current, diff);
+            let next = current + diff;
             if map.walls.contains(&next) {
                 // Hit a wall, abort this instruction.
                 continue 'ins;
@@ -57,7 +57,7 @@ fn part_1((map, robot, instructions): &(Map, Coord, Vec<Direction>)) -> isize {
             if map.boxes.contains(&next) {
                 // Need to move more boxes.
                 boxes_to_move.push(next);
-                current = add_coords(current, diff);
+                current += diff;
             } else {
                 // Found empty space.
                 break 'bxs;
@@ -68,121 +68,167 @@ fn part_1((map, robot, instructions): &(Map, Coord, Vec<Direction>)) -> isize {
             map.boxes.remove(b);
         }
         for b in boxes_to_move {
-            map.boxes.insert(add_coords(b, diff));
+            map.boxes.insert(b + diff);
         }
-        robot = add_coords(robot, diff);
+        robot += diff;
     }
 
-    map.boxes.iter().map(|b| b.0 + b.1 * 100).sum()
+    frames.record(|| map.image(robot, 1).scaled(4));
+
+    map.boxes.iter().map(|b| b.x + b.y * 100).sum()
 }
 
-fn part_2((map, robot, instructions): &(Map, Coord, Vec<Direction>)) -> isize {
-    let mut map = map.to_owned();
-    let mut robot = robot.to_owned();
+fn part_2((map, robot, instructions): &(Map, Coordinate, Vec<Direction>)) -> isize {
+    let (mut map, mut robot) = map.widen(*robot);
+    let mut frames = Frames::new("2024-15-part2");
+    for (i, instruction) in instructions.iter().enumerate() {
+        if i.is_multiple_of(FRAME_INTERVAL) {
+            frames.record(|| map.image(robot, 2).scaled(4));
+        }
+        robot = map.push_wide(robot, *instruction);
+    }
+    frames.record(|| map.image(robot, 2).scaled(4));
 
-    // Shift everything over.
-    robot.0 *= 2;
-    // Boxes still identified by their left coordinate.
-    map.boxes = map.boxes.iter().map(|(x, y)| (x * 2, *y)).collect();
-    // Walls are just duplicated for simplicity.
-    map.walls = map
-        .walls
-        .iter()
-        .flat_map(|(x, y)| [(x * 2, *y), (x * 2 + 1, *y)])
-        .collect();
+    map.boxes.iter().map(|b| b.x + b.y * 100).sum()
+}
 
-    // Same as part 1, but with extra box shifting.
-    'ins: for instruction in instructions {
-        let diff = instruction.diff();
+/// Follows the instructions of part 2 one at a time.
+pub fn replay() -> Box<dyn Simulation> {
+    let (map, robot, instructions) = parse(include_str!("inputs/15"));
+    let (map, robot) = map.widen(robot);
+    Box::new(Replay {
+        map,
+        robot,
+        instructions: instructions.into_iter(),
+    })
+}
+
+struct Replay {
+    map: Map,
+    robot: Coordinate,
+    instructions: std::vec::IntoIter<Direction>,
+}
+
+impl Simulation for Replay {
+    fn step(&mut self) -> bool {
+        let Some(instruction) = self.instructions.next() else {
+            return false;
+        };
+        self.robot = self.map.push_wide(self.robot, instruction);
+        true
+    }
+
+    fn image(&self) -> Image {
+        self.map.image(self.robot, 2)
+    }
+}
+
+#[derive(Clone)]
+struct Map {
+    walls: FxHashSet<Coordinate>,
+    boxes: FxHashSet<Coordinate>,
+}
+
+impl Map {
+    /// Returns the map and robot position of part 2, where everything but the robot is twice as
+    /// wide. Boxes are still identified by their left coordinate.
+    fn widen(&self, mut robot: Coordinate) -> (Self, Coordinate) {
+        let mut map = self.clone();
+        robot.x *= 2;
+        map.boxes = map
+            .boxes
+            .iter()
+            .map(|b| Coordinate::new(b.x * 2, b.y))
+            .collect();
+        // Walls are just duplicated for simplicity.
+        map.walls = map
+            .walls
+            .iter()
+            .flat_map(|w| {
+                let left = Coordinate::new(w.x * 2, w.y);
+                [left, left + RIGHT]
+            })
+            .collect();
+        (map, robot)
+    }
+
+    /// Moves the robot one step on the widened map, if it's not blocked, along with any boxes it
+    /// pushes. Returns the new position of the robot.
+    fn push_wide(&mut self, robot: Coordinate, instruction: Direction) -> Coordinate {
+        let diff = instruction.offset();
         let mut boxes_to_move = FxHashSet::default();
-        let mut pushed_on = FxHashSet::from_iter([add_coords(robot, diff)]);
+        let mut pushed_on = FxHashSet::from_iter([robot + diff]);
         'bxs: loop {
             if pushed_on.is_empty() {
                 // Everything is free, we can push this way.
                 break 'bxs;
             }
             // Are we pushing onto a wall anywhere?
-            if !pushed_on.is_disjoint(&map.walls) {
+            if !pushed_on.is_disjoint(&self.walls) {
                 // Hit a wall, abort this instruction.
-                continue 'ins;
+                return robot;
             }
             let mut new_pushed_on = FxHashSet::default();
             for &centre in pushed_on.iter() {
-                let left_of_centre = add_coords(centre, (-1, 0));
-                let right_of_centre = add_coords(centre, (1, 0));
+                let left_of_centre = centre + LEFT;
+                let right_of_centre = centre + RIGHT;
 
                 // Do we hit a box, and if so where?
                 // Depends on the direction we push on as well.
                 match instruction {
                     Direction::Up | Direction::Down => {
-                        if map.boxes.contains(&centre) {
+                        if self.boxes.contains(&centre) {
                             // Hit the left side of the box.
                             boxes_to_move.insert(centre);
-                            new_pushed_on.insert(add_coords(centre, diff));
-                            new_pushed_on.insert(add_coords(right_of_centre, diff));
-                        } else if map.boxes.contains(&left_of_centre) {
+                            new_pushed_on.insert(centre + diff);
+                            new_pushed_on.insert(right_of_centre + diff);
+                        } else if self.boxes.contains(&left_of_centre) {
                             // Hit the right side of the box.
                             boxes_to_move.insert(left_of_centre);
-                            new_pushed_on.insert(add_coords(left_of_centre, diff));
-                            new_pushed_on.insert(add_coords(centre, diff));
+                            new_pushed_on.insert(left_of_centre + diff);
+                            new_pushed_on.insert(centre + diff);
                         };
                     }
                     Direction::Right => {
-                        if map.boxes.contains(&centre) {
+                        if self.boxes.contains(&centre) {
                             boxes_to_move.insert(centre);
-                            new_pushed_on.insert(add_coords(right_of_centre, diff));
+                            new_pushed_on.insert(right_of_centre + diff);
                         };
                     }
                     Direction::Left => {
-                        if map.boxes.contains(&left_of_centre) {
+                        if self.boxes.contains(&left_of_centre) {
                             boxes_to_move.insert(left_of_centre);
-                            new_pushed_on.insert(add_coords(left_of_centre, diff));
+                            new_pushed_on.insert(left_of_centre + diff);
                         }
                     }
+                    _ => unreachable!("robots only move orthogonally"),
                 };
             }
             pushed_on = new_pushed_on;
         }
         // Two-pass to avoid removing boxes we just added.
         for b in boxes_to_move.iter() {
-            map.boxes.remove(b);
+            self.boxes.remove(b);
         }
         for b in boxes_to_move {
-            map.boxes.insert(add_coords(b, diff));
+            self.boxes.insert(b + diff);
         }
-        robot = add_coords(robot, diff);
+        robot + diff
     }
 
-    map.boxes.iter().map(|b| b.0 + b.1 * 100).sum()
-}
-
-type Coord = (isize, isize);
-
-fn add_coords(a: Coord, b: Coord) -> Coord {
-    (a.0 + b.0, a.1 + b.1)
-}
-
-#[derive(Clone)]
-struct Map {
-    walls: FxHashSet<Coord>,
-    boxes: FxHashSet<Coord>,
-}
-
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn diff(&self) -> Coord {
-        match self {
-            Self::Right => (1, 0),
-            Self::Left => (-1, 0),
-            Self::Up => (0, -1),
-            Self::Down => (0, 1),
+    /// Draws the warehouse, with boxes `box_width` wide from their left coordinate.
+    fn image(&self, robot: Coordinate, box_width: isize) -> Image {
+        let bounds = Bounds::enclosing(self.walls.iter().copied()).unwrap_or_default();
+        let mut image = Image::new(bounds.width(), bounds.height(), Rgb::BLACK);
+        self.walls
+            .iter()
+            .for_each(|&w| image.set(w - bounds.min, Rgb::GREY));
+        for &b in &self.boxes {
+            (0..box_width)
+                .for_each(|dx| image.set(Coordinate::new(b.x + dx, b.y) - bounds.min, Rgb::BROWN));
         }
+        image.set(robot - bounds.min, Rgb::YELLOW);
+        image
     }
 }
 
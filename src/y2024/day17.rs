@@ -1,25 +1,22 @@
+use crate::parsing::{field, number, numbers, parse_all, PResult};
 use itertools::Itertools;
-use nom::bytes::complete::tag;
-use nom::character::complete::{char, newline, u128 as parse_u128};
-use nom::multi::separated_list1;
-use nom::sequence::preceded;
-use nom::IResult;
+use nom::character::complete::{char, line_ending};
+use nom::multi::count;
 
 crate::solve!("17");
 
 fn parse(input: &str) -> Computer {
-    parse_computer(input).expect("invalid computer").1
+    parse_all(input, parse_computer)
 }
 
-fn parse_computer(s: &str) -> IResult<&str, Computer> {
-    let (s, a) = preceded(tag("Register A: "), parse_u128)(s)?;
-    let (s, _) = newline(s)?;
-    let (s, b) = preceded(tag("Register B: "), parse_u128)(s)?;
-    let (s, _) = newline(s)?;
-    let (s, c) = preceded(tag("Register C: "), parse_u128)(s)?;
-    let (s, _) = newline(s)?;
-    let (s, _) = newline(s)?;
-    let (s, program) = preceded(tag("Program: "), separated_list1(char(','), parse_u128))(s)?;
+fn parse_computer(s: &str) -> PResult<'_, Computer> {
+    let (s, a) = field("Register A", number)(s)?;
+    let (s, _) = line_ending(s)?;
+    let (s, b) = field("Register B", number)(s)?;
+    let (s, _) = line_ending(s)?;
+    let (s, c) = field("Register C", number)(s)?;
+    let (s, _) = count(line_ending, 2)(s)?;
+    let (s, program) = field("Program", numbers(char(',')))(s)?;
 
     Ok((
         s,
@@ -1,7 +1,9 @@
-use nom::character::complete::{char, u64};
+use crate::coordinate::Coordinate;
+use crate::grid::Grid;
+use crate::pathfinding::bfs;
+use crate::unionfind::UnionFind;
+use nom::character::complete::{char, i64};
 use nom::sequence::separated_pair;
-use std::cmp::{Ordering, Reverse};
-use std::collections::BinaryHeap;
 
 crate::solve!("18");
 
@@ -9,103 +11,74 @@ fn parse(input: &str) -> Vec<Coordinate> {
     input
         .lines()
         .map(|l| {
-            separated_pair(u64::<&str, ()>, char(','), u64)(l)
+            separated_pair(i64::<&str, ()>, char(','), i64)(l)
                 .expect("invalid pair")
                 .1
         })
-        .map(|(a, b)| (a as usize, b as usize).into())
+        .map(|(a, b)| Coordinate::new(a as isize, b as isize))
         .collect()
 }
 
 fn part_1(input: &[Coordinate]) -> usize {
     let test_mode = input.len() < 1024;
     let size = if test_mode { 7 } else { 71 };
-    let mut corruption = [[false; 71]; 71];
-    input
-        .iter()
-        .take(if test_mode { 12 } else { 1024 })
-        .for_each(|&Coordinate { x, y }| corruption[x][y] = true);
-    steps_required(&corruption, size).expect("no solution found")
+    let fallen = if test_mode { 12 } else { 1024 };
+    steps_required(&corruption(&input[..fallen], size)).expect("no solution found")
 }
 
 fn part_2(input: &[Coordinate]) -> String {
     let test_mode = input.len() < 1024;
     let size = if test_mode { 7 } else { 71 };
-    let indices: Vec<usize> = (0..=input.len()).collect();
-    let time = indices.partition_point(|n| {
-        let mut corruption = [[false; 71]; 71];
-        input
-            .iter()
-            .take(*n)
-            .for_each(|&Coordinate { x, y }| corruption[x][y] = true);
-        steps_required(&corruption, size).is_some()
-    });
-    let fatal_byte = input[time - 1];
+    let fatal_byte = first_blocking_byte(input, size).expect("exit never blocked");
     format!("{},{}", fatal_byte.x, fatal_byte.y)
 }
 
-fn steps_required(corruption: &[[bool; 71]; 71], size: usize) -> Option<usize> {
-    let mut visited = [[false; 71]; 71];
-    visited[0][0] = true;
-
-    let mut queue = BinaryHeap::from_iter([Reverse((0, Coordinate::default()))]);
-    while let Some(Reverse((steps, coord))) = queue.pop() {
-        for neighbour in coord.neighbours(size) {
-            if neighbour == (size - 1, size - 1).into() {
-                return Some(steps + 1);
-            }
-            if visited[neighbour.x][neighbour.y] || corruption[neighbour.x][neighbour.y] {
-                continue;
+/// Returns the first byte to cut the start off from the exit. That happens when fallen bytes,
+/// touching each other including diagonally, connect the top or right edge to the bottom or left
+/// edge.
+fn first_blocking_byte(bytes: &[Coordinate], size: usize) -> Option<Coordinate> {
+    let mut fallen = Grid::new(size, size, false);
+    let index = |c: Coordinate| c.y as usize * size + c.x as usize;
+    let (top_right, bottom_left) = (size * size, size * size + 1);
+    let mut walls = UnionFind::new(size * size + 2);
+    let last = size as isize - 1;
+    bytes.iter().copied().find(|&byte| {
+        fallen[byte] = true;
+        for neighbour in fallen.diagonal_neighbours(byte) {
+            if fallen[neighbour] {
+                walls.union(index(byte), index(neighbour));
             }
-            visited[neighbour.x][neighbour.y] = true;
-            queue.push(Reverse((steps + 1, neighbour)));
         }
-    }
-
-    None
-}
-
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Default, Debug)]
-struct Coordinate {
-    x: usize,
-    y: usize,
-}
-
-impl From<(usize, usize)> for Coordinate {
-    fn from((x, y): (usize, usize)) -> Self {
-        Self { x, y }
-    }
-}
-
-impl PartialOrd for Coordinate {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+        if byte.y == 0 || byte.x == last {
+            walls.union(index(byte), top_right);
+        }
+        if byte.x == 0 || byte.y == last {
+            walls.union(index(byte), bottom_left);
+        }
+        walls.connected(top_right, bottom_left)
+    })
 }
 
-impl Ord for Coordinate {
-    fn cmp(&self, other: &Self) -> Ordering {
-        (self.x + self.y).cmp(&(other.x + other.y)).reverse()
+/// Returns the memory space with `bytes` corrupted.
+fn corruption(bytes: &[Coordinate], size: usize) -> Grid<bool> {
+    let mut corruption = Grid::new(size, size, false);
+    for &byte in bytes {
+        corruption[byte] = true;
     }
+    corruption
 }
 
-impl Coordinate {
-    fn neighbours(&self, size: usize) -> Vec<Self> {
-        let mut rv = vec![];
-        if self.x > 0 {
-            rv.push((self.x - 1, self.y).into());
-        }
-        if self.x < size - 1 {
-            rv.push((self.x + 1, self.y).into());
-        }
-        if self.y > 0 {
-            rv.push((self.x, self.y - 1).into());
-        }
-        if self.y < size - 1 {
-            rv.push((self.x, self.y + 1).into());
-        }
-        rv
-    }
+fn steps_required(corruption: &Grid<bool>) -> Option<usize> {
+    let exit = Coordinate::new(
+        corruption.width() as isize - 1,
+        corruption.height() as isize - 1,
+    );
+    bfs(
+        [Coordinate::default()],
+        |c| corruption.neighbours(c).filter(|&n| !corruption[n]),
+        |c| c == exit,
+    )
+    .goal_cost()
 }
 
 #[cfg(test)]
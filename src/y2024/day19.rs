@@ -1,3 +1,4 @@
+use crate::memo::Memo;
 use crate::print_results;
 use itertools::Itertools;
 use nom::bytes::complete::tag;
@@ -9,12 +10,16 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/19");
+    let (pt1, pt2) = run(include_str!("inputs/19"));
+    print_results(2024, 19, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let (towels, patterns) = parse(input);
     let results: Vec<_> = patterns.par_iter().map(|p| ways(p, &towels)).collect();
     let pt1 = results.iter().filter(|n| **n > 0).count();
     let pt2: usize = results.iter().sum();
-    print_results(2024, 19, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn parse(s: &str) -> (Vec<&str>, Vec<&str>) {
@@ -27,7 +32,7 @@ fn parse(s: &str) -> (Vec<&str>, Vec<&str>) {
     .1
 }
 
-fn ways(pattern: &str, towels: &[&'static str]) -> usize {
+fn ways(pattern: &str, towels: &[&str]) -> usize {
     // For all stripes, collect possible towel positions that could cover that stripe.
     let crucial_stripes = pattern.char_indices().map(|(idx, c)|
         // Try each of the towels.
@@ -60,32 +65,36 @@ fn ways(pattern: &str, towels: &[&'static str]) -> usize {
 
     // Check that there is a combination of towels that cover the crucial stripes without
     // overlapping through DFS.
-    all_stripes_covered(None, crucial_stripes)
+    all_stripes_covered(&mut Memo::new(), None, &crucial_stripes)
 }
 
-#[memoize::memoize(CustomHasher: ahash::AHashMap)]
+/// A towel placed at a stripe: the stripe's index, the towel, and the stripe's index in the towel.
+type Placement<'a> = (usize, &'a str, usize);
+
+/// Counts the ways to cover the remaining `stripes`, memoized by how many stripes remain, which
+/// is enough within one pattern.
 fn all_stripes_covered(
+    memo: &mut Memo<(Option<usize>, usize), usize>,
     last_towel_end: Option<usize>,
-    stripes: Vec<Vec<(usize, &'static str, usize)>>,
+    stripes: &[Vec<Placement>],
 ) -> usize {
     let Some(options) = stripes.first() else {
         // Covered everything, success.
         return 1;
     };
-    if last_towel_end.is_some_and(|lte| lte >= options.first().unwrap().0) {
-        // This stripe already got covered by the previous towel.
-        return all_stripes_covered(last_towel_end, stripes.into_iter().skip(1).collect());
-    }
-    options
-        .iter()
-        .filter(|(idx, _, anchor)| last_towel_end.is_none_or(|lte| lte < idx - anchor))
-        .map(|(idx, towel, anchor)| {
-            all_stripes_covered(
-                Some(idx + towel.len() - anchor - 1),
-                stripes.iter().skip(1).cloned().collect(),
-            )
-        })
-        .sum()
+    memo.get_or_insert_with((last_towel_end, stripes.len()), |memo| {
+        if last_towel_end.is_some_and(|lte| lte >= options.first().unwrap().0) {
+            // This stripe already got covered by the previous towel.
+            return all_stripes_covered(memo, last_towel_end, &stripes[1..]);
+        }
+        options
+            .iter()
+            .filter(|(idx, _, anchor)| last_towel_end.is_none_or(|lte| lte < idx - anchor))
+            .map(|(idx, towel, anchor)| {
+                all_stripes_covered(memo, Some(idx + towel.len() - anchor - 1), &stripes[1..])
+            })
+            .sum()
+    })
 }
 
 #[cfg(test)]
@@ -115,4 +124,11 @@ bbrgwb";
         let (towels, patterns) = parse(INPUT);
         assert_eq!(patterns.iter().map(|p| ways(p, &towels)).sum::<usize>(), 16);
     }
+
+    #[test]
+    fn test_run() {
+        // Inputs arriving at runtime only live as long as the request.
+        let input = INPUT.to_string();
+        assert_eq!(run(&input), ("6".to_string(), "16".to_string()));
+    }
 }
@@ -1,41 +1,47 @@
 use crate::coordinate::Coordinate;
+use crate::pathfinding::bfs;
 use crate::{coordinate, print_results};
-use itertools::Itertools;
-use rustc_hash::{FxHashMap, FxHashSet};
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use ahash::HashMap;
+use rustc_hash::FxHashSet;
 use std::time::Instant;
 
 pub fn solve() {
     let t = Instant::now();
-    let (start, end, walls) = parse_input(include_str!("inputs/20"));
+    let (pt1, pt2) = run(include_str!("inputs/20"));
+    print_results(2024, 20, pt1, pt2, Some(t));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let (start, end, walls) = parse_input(input);
     let costs = costs(&walls, start);
     let baseline = *costs.get(&end).unwrap();
     let pt1 = cheats_saving_n_ps(100, baseline, &costs, 2);
     let pt2 = cheats_saving_n_ps(100, baseline, &costs, 20);
-    print_results(2024, 20, pt1, pt2, Some(t));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn cheats_saving_n_ps(
     min_savings: usize,
     baseline: usize,
-    costs: &FxHashMap<Coordinate, usize>,
+    costs: &HashMap<Coordinate, usize>,
     max_cheat_len: usize,
 ) -> usize {
     costs
         .iter()
-        .cartesian_product(costs.iter())
-        .filter(|&((from, from_cost), (to, to_cost))| {
-            if !(2..=max_cheat_len).contains(&from.manhattan_distance(*to)) {
-                return false;
-            }
-            let new_cost = from_cost + from.manhattan_distance(*to) + to_cost.abs_diff(baseline);
-            if new_cost + min_savings <= baseline {
-                return true;
-            }
-            false
+        .map(|(from, from_cost)| {
+            from.within_manhattan_radius(max_cheat_len)
+                .filter(|to| from.manhattan_distance(*to) >= 2)
+                .filter(|to| {
+                    let Some(to_cost) = costs.get(to) else {
+                        return false;
+                    };
+                    let new_cost =
+                        from_cost + from.manhattan_distance(*to) + to_cost.abs_diff(baseline);
+                    new_cost + min_savings <= baseline
+                })
+                .count()
         })
-        .count()
+        .sum()
 }
 
 fn parse_input(input: &str) -> (Coordinate, Coordinate, FxHashSet<Coordinate>) {
@@ -55,27 +61,18 @@ fn parse_input(input: &str) -> (Coordinate, Coordinate, FxHashSet<Coordinate>) {
     (start, end, walls)
 }
 
-fn costs(walls: &FxHashSet<Coordinate>, start: Coordinate) -> FxHashMap<Coordinate, usize> {
-    let mut queue: BinaryHeap<(Reverse<usize>, Coordinate)> =
-        BinaryHeap::from_iter([(Reverse(0), start)]);
-    let mut costs = FxHashMap::from_iter([(start, 0)]);
-
-    while let Some((Reverse(time), position)) = queue.pop() {
-        let options = coordinate::DIRECTIONS.map(|d| (d, walls.contains(&(position + d))));
-        for (direction, is_wall) in options {
-            let new_position = position + direction;
-            if is_wall {
-                continue;
-            }
-            if costs.contains_key(&new_position) {
-                continue;
-            }
-            costs.insert(new_position, time + 1);
-            queue.push((Reverse(time + 1), new_position));
-        }
-    }
-
-    costs
+fn costs(walls: &FxHashSet<Coordinate>, start: Coordinate) -> HashMap<Coordinate, usize> {
+    bfs(
+        [start],
+        |position| {
+            coordinate::DIRECTIONS
+                .map(|d| position + d)
+                .into_iter()
+                .filter(|p| !walls.contains(p))
+        },
+        |_| false,
+    )
+    .costs
 }
 
 #[cfg(test)]
@@ -5,10 +5,15 @@ use std::time::Instant;
 
 pub fn solve() {
     let t = Instant::now();
-    let codes = include_str!("inputs/21").lines().collect_vec();
+    let (pt1, pt2) = run(include_str!("inputs/21"));
+    print_results(2024, 20, pt1, pt2, Some(t));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let codes = input.lines().collect_vec();
     let pt1 = codes.iter().map(|code| complexity(code, 3)).sum::<usize>();
     let pt2 = codes.iter().map(|code| complexity(code, 26)).sum::<usize>();
-    print_results(2024, 20, pt1, pt2, Some(t));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn complexity(code: &str, operators: usize) -> usize {
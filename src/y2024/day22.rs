@@ -7,14 +7,19 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input: Vec<i64> = include_str!("inputs/22")
+    let (pt1, pt2) = run(include_str!("inputs/22"));
+    print_results(2024, 22, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
+    let input: Vec<i64> = input
         .lines()
         .map(|line| line.parse().unwrap())
         .collect_vec();
     let secrets = input.iter().map(|s| secrets(*s)).collect_vec();
     let pt1: i64 = secrets.iter().map(|ss| ss.last().unwrap()).sum();
     let pt2 = optimum_sales(&secrets);
-    print_results(2024, 22, pt1, pt2, Some(start));
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn optimum_sales(secrets: &[Vec<i64>]) -> i64 {
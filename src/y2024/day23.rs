@@ -1,60 +1,30 @@
-use crate::print_results;
+use crate::{graph, print_results};
 use ahash::{HashMap, HashMapExt, HashSet};
-use itertools::Itertools;
 use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/23");
+    let (pt1, pt2) = run(include_str!("inputs/23"));
+    print_results(2024, 23, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let connections = build_connections(input);
-    print_results(
-        2024,
-        23,
-        part_1(&connections),
-        part_2(&connections),
-        Some(start),
-    );
+    (
+        part_1(&connections).to_string(),
+        part_2(&connections).to_string(),
+    )
 }
 
 fn part_1(connections: &HashMap<&str, HashSet<&str>>) -> usize {
-    connections
+    graph::cliques(connections, 3)
         .iter()
-        .filter(|(_, vs)| vs.len() >= 2)
-        .flat_map(|(k, v)| {
-            v.iter()
-                .tuple_combinations()
-                .filter(|&(a, b)| connections[a].contains(b))
-                .map(move |(a, b)| [k, a, b].into_iter().sorted_unstable().collect_vec())
-        })
         .filter(|cs| cs.iter().any(|c| c.starts_with("t")))
-        .unique()
         .count()
 }
 
 fn part_2(connections: &HashMap<&str, HashSet<&str>>) -> String {
-    largest_cluster(connections)
-        .iter()
-        .sorted_unstable()
-        .join(",")
-}
-
-fn largest_cluster<'a>(connections: &HashMap<&'a str, HashSet<&'a str>>) -> HashSet<&'a str> {
-    let mut largest_so_far = HashSet::default();
-    for (&k, peers) in connections.iter() {
-        if largest_so_far.contains(k) {
-            continue;
-        }
-        let mut cluster = HashSet::from_iter([k]);
-        peers.iter().for_each(|peer| {
-            if cluster.is_subset(&connections[peer]) {
-                cluster.insert(peer);
-            }
-        });
-        if cluster.len() > largest_so_far.len() {
-            largest_so_far = cluster;
-        }
-    }
-    largest_so_far
+    graph::maximum_clique(connections).join(",")
 }
 
 fn build_connections(input: &str) -> HashMap<&str, HashSet<&str>> {
@@ -6,11 +6,15 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/24");
+    let (pt1, pt2) = run(include_str!("inputs/24"));
+    print_results(2024, 24, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let (initial_values, mappings) = parse_input(input);
     let pt1 = part_1(&initial_values, &mappings);
     let pt2 = part_2(&initial_values, &mappings);
-    print_results(2024, 24, pt1, pt2, Some(start))
+    (pt1.to_string(), pt2.to_string())
 }
 
 fn part_1(
@@ -348,7 +352,7 @@ type Parsed<'a> = (
     HashMap<&'a str, (&'a str, &'a str, Gate)>,
 );
 
-fn parse_input(s: &str) -> Parsed {
+fn parse_input(s: &str) -> Parsed<'_> {
     let initial_values: HashMap<_, _> = s
         .lines()
         .take_while(|l| !l.is_empty())
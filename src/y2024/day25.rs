@@ -3,7 +3,11 @@ use std::time::Instant;
 
 pub fn solve() {
     let start = Instant::now();
-    let input = include_str!("inputs/25");
+    let (pt1, pt2) = run(include_str!("inputs/25"));
+    print_results(2024, 25, pt1, pt2, Some(start));
+}
+
+pub fn run(input: &str) -> (String, String) {
     let keyings = parse_keyings(input);
     let pt1 = keyings
         .iter()
@@ -22,7 +26,7 @@ pub fn solve() {
                 .count()
         })
         .sum::<usize>();
-    print_results(2024, 25, pt1, 0, Some(start));
+    (pt1.to_string(), 0.to_string())
 }
 
 type Keying = [u32; 5];